@@ -0,0 +1,87 @@
+//! Entity extraction throughput on large bodies
+//!
+//! Run with `cargo bench --bench extract`. Prints the mean time per
+//! `ExtractedEntities::extract` call, or `EntityStream` run fed in 8 KiB
+//! chunks, for each corpus.
+
+use email_extract::{EntityStream, ExtractedEntities, ExtractionOptions};
+use std::fmt::Write;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Minimum measuring time per corpus
+const MEASURE: Duration = Duration::from_secs(2);
+
+/// Newsletter text with links, prices, contacts and tracking references
+fn newsletter(items: usize) -> String {
+    let mut text = String::new();
+    for i in 0..items {
+        writeln!(
+            text,
+            "Deal #{i}: Wireless headphones now $49.99 (was $79.99), 2x for $90.00.\n\
+             Shop now: https://shop.example.com/p/{i}?utm_source=newsletter&utm_medium=email\n\
+             Questions? Write to support@example.com or call (555) 123-4567 before 5:30 PM EST.\n\
+             Follow @exampleshop and #summersale. Order ORD-{i:06} ships from 192.0.2.{}.\n",
+            i % 255
+        )
+        .unwrap();
+    }
+    text
+}
+
+/// Plain prose without any entity
+fn prose(paragraphs: usize) -> String {
+    "The quick brown fox jumps over the lazy dog while the team reviews the \
+     quarterly plan and discusses the next steps for the project in detail.\n\n"
+        .repeat(paragraphs)
+}
+
+/// Extract the whole text at once
+fn single_pass(text: &str) -> ExtractedEntities {
+    ExtractedEntities::extract(text)
+}
+
+/// Feed the text to an `EntityStream` in 8 KiB chunks
+fn streamed(text: &str) -> ExtractedEntities {
+    let options = ExtractionOptions::default();
+    let mut stream = EntityStream::new(&options);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(8 * 1024);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        stream.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    stream.finish()
+}
+
+fn bench(name: &str, text: &str, extract: fn(&str) -> ExtractedEntities) {
+    let start = Instant::now();
+    let mut runs = 0u32;
+    while start.elapsed() < MEASURE || runs < 3 {
+        black_box(extract(black_box(text)));
+        runs += 1;
+    }
+    let mean = start.elapsed() / runs;
+    println!(
+        "{name:<28} {:>8} KiB {:>10.3} ms/iter ({runs} runs)",
+        text.len() / 1024,
+        mean.as_secs_f64() * 1000.0
+    );
+}
+
+fn main() {
+    let newsletter = newsletter(2000);
+    let prose = prose(4000);
+    bench("newsletter", &newsletter, single_pass);
+    bench("newsletter, streamed", &newsletter, streamed);
+    bench("prose", &prose, single_pass);
+    bench("prose, streamed", &prose, streamed);
+    bench(
+        "newsletter + prose",
+        &(prose[..prose.len() * 3 / 4].to_string() + &newsletter[..newsletter.len() / 10]),
+        single_pass,
+    );
+}
@@ -0,0 +1,107 @@
+//! Minimal iCalendar (RFC 5545) parsing for meeting invitations
+
+use crate::mime;
+use crate::types::{CalendarEvent, CalendarMethod};
+
+/// Find the first `text/calendar` or `application/ics` part and parse it
+pub fn find_calendar_event(parsed: &mailparse::ParsedMail) -> Option<CalendarEvent> {
+    if parsed.subparts.is_empty() {
+        let mimetype = parsed.ctype.mimetype.to_lowercase();
+        if mimetype != "text/calendar" && mimetype != "application/ics" {
+            return None;
+        }
+        let raw = parsed.get_body_raw().ok()?;
+        let text = mime::decode_charset_lossy(&raw, &parsed.ctype.charset);
+        let mut event = parse_ics(&text)?;
+        // The Content-Type `method` parameter mirrors METHOD (RFC 6047)
+        if event.method.is_none() {
+            event.method = parsed
+                .ctype
+                .params
+                .get("method")
+                .map(|m| CalendarMethod::parse(m));
+        }
+        return Some(event);
+    }
+
+    parsed.subparts.iter().find_map(find_calendar_event)
+}
+
+/// Parse the calendar METHOD and the first VEVENT of an iCalendar object
+pub fn parse_ics(text: &str) -> Option<CalendarEvent> {
+    let mut event = CalendarEvent::default();
+    let mut in_event = false;
+    let mut seen_event = false;
+
+    for line in unfold(text) {
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name_params
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let value = value.trim();
+
+        match name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VEVENT") && !seen_event => {
+                in_event = true;
+                seen_event = true;
+            }
+            "END" if value.eq_ignore_ascii_case("VEVENT") => in_event = false,
+            "METHOD" => event.method = Some(CalendarMethod::parse(value)),
+            _ if !in_event => {}
+            "UID" => event.uid = Some(value.to_string()),
+            "SUMMARY" => event.summary = Some(unescape(value)),
+            "LOCATION" => event.location = Some(unescape(value)),
+            "DTSTART" => event.start = Some(value.to_string()),
+            "DTEND" => event.end = Some(value.to_string()),
+            "STATUS" => event.status = Some(value.to_ascii_uppercase()),
+            "SEQUENCE" => event.sequence = value.parse().ok(),
+            "ORGANIZER" => event.organizer = Some(strip_mailto(value)),
+            "ATTENDEE" => event.attendees.push(strip_mailto(value)),
+            _ => {}
+        }
+    }
+
+    (seen_event || event.method.is_some()).then_some(event)
+}
+
+/// Join folded continuation lines (leading space or tab)
+pub fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Undo TEXT value escaping (`\,`, `\;`, `\n`, `\\`)
+pub fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n' | 'N') => result.push('\n'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn strip_mailto(value: &str) -> String {
+    value
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+        .map_or(value, |_| &value[7..])
+        .to_string()
+}
@@ -0,0 +1,146 @@
+//! Delivery status notifications (RFC 3464) and enhanced status codes
+//! (RFC 3463)
+
+use crate::mime;
+use crate::types::{BounceReason, BounceType, DeliveryStatus};
+use regex::Regex;
+
+static STATUS_CODE_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"\b([245])\.(\d{1,3})\.(\d{1,3})\b").unwrap());
+
+/// Collect the per-recipient reports of every `message/delivery-status` part
+pub fn find_delivery_status(parsed: &mailparse::ParsedMail) -> Vec<DeliveryStatus> {
+    if !parsed.subparts.is_empty() {
+        return parsed
+            .subparts
+            .iter()
+            .flat_map(find_delivery_status)
+            .collect();
+    }
+
+    let mimetype = parsed.ctype.mimetype.to_lowercase();
+    if mimetype != "message/delivery-status" && mimetype != "message/global-delivery-status" {
+        return Vec::new();
+    }
+    parsed.get_body_raw().map_or_else(
+        |_| Vec::new(),
+        |raw| parse_delivery_status(&mime::decode_charset_lossy(&raw, &parsed.ctype.charset)),
+    )
+}
+
+/// Parse the recipient field groups following the per-message fields
+pub fn parse_delivery_status(text: &str) -> Vec<DeliveryStatus> {
+    let mut reports = Vec::new();
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    // Groups are separated by blank lines; the first one describes the
+    // message and has no Final-Recipient
+    for line in text.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() {
+            reports.extend(recipient_report(&fields));
+            fields.clear();
+        } else if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = fields.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            fields.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    reports
+}
+
+fn recipient_report(fields: &[(String, String)]) -> Option<DeliveryStatus> {
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    let recipient = strip_address_type(&field("final-recipient")?);
+    let diagnostic_code = field("diagnostic-code").map(|code| strip_address_type(&code));
+    let action = field("action").map(|action| action.to_ascii_lowercase());
+    // The Status field is required, but some MTAs only put the code in
+    // the diagnostic text
+    let status = field("status")
+        .as_deref()
+        .and_then(status_code)
+        .or_else(|| diagnostic_code.as_deref().and_then(status_code));
+
+    let reason = status.as_deref().map_or(BounceReason::Other, reason_for);
+    let bounce = bounce_type(action.as_deref(), status.as_deref(), reason);
+
+    Some(DeliveryStatus {
+        recipient,
+        original_recipient: field("original-recipient").map(|r| strip_address_type(&r)),
+        action,
+        status,
+        diagnostic_code,
+        remote_mta: field("remote-mta").map(|mta| strip_address_type(&mta)),
+        reason,
+        bounce,
+    })
+}
+
+/// Drop the `rfc822;` / `smtp;` / `dns;` type prefix of a field value
+fn strip_address_type(value: &str) -> String {
+    match value.split_once(';') {
+        Some((kind, rest)) if !kind.contains(' ') && !kind.contains('@') => rest.trim(),
+        _ => value.trim(),
+    }
+    .to_string()
+}
+
+/// First enhanced status code (`class.subject.detail`) in the text
+fn status_code(text: &str) -> Option<String> {
+    STATUS_CODE_REGEX
+        .find(text)
+        .map(|code| code.as_str().to_string())
+}
+
+/// Category of an enhanced status code, ignoring its class
+fn reason_for(status: &str) -> BounceReason {
+    let mut parts = status.splitn(3, '.').skip(1);
+    let subject = parts.next().unwrap_or_default();
+    let detail = parts.next().unwrap_or_default();
+
+    match (subject, detail) {
+        ("1", "1" | "10") => BounceReason::UnknownUser,
+        ("1", "2") => BounceReason::UnknownDomain,
+        ("1", "3") => BounceReason::InvalidAddress,
+        ("2", "1") => BounceReason::MailboxDisabled,
+        ("2", "2") => BounceReason::MailboxFull,
+        ("2", "3") | ("3", "4") => BounceReason::MessageTooLarge,
+        ("4", "7") => BounceReason::Timeout,
+        ("4", _) => BounceReason::RoutingError,
+        ("7", _) => BounceReason::PolicyRejection,
+        _ => BounceReason::Other,
+    }
+}
+
+/// Hard or soft failure; `None` for delivered, relayed or delayed reports
+/// and codes of class 2
+///
+/// Class 4 codes are soft. Class 5 codes are hard except a full mailbox or
+/// a timeout, which usually clear up on their own.
+fn bounce_type(
+    action: Option<&str>,
+    status: Option<&str>,
+    reason: BounceReason,
+) -> Option<BounceType> {
+    if matches!(action, Some("delivered" | "relayed" | "expanded")) {
+        return None;
+    }
+    match status.and_then(|code| code.chars().next()) {
+        Some('5') if matches!(reason, BounceReason::MailboxFull | BounceReason::Timeout) => {
+            Some(BounceType::Soft)
+        }
+        Some('5') => Some(BounceType::Hard),
+        Some('4') => Some(BounceType::Soft),
+        None if action == Some("failed") => Some(BounceType::Hard),
+        _ => None,
+    }
+}
@@ -0,0 +1,93 @@
+//! Tabular export of extracted entities
+
+use crate::types::Email;
+use std::io::Write;
+
+/// Write one CSV row per extracted entity across a batch of emails
+///
+/// Columns: `uid`, `message_id`, `entity_type`, `value`, `context`. The
+/// context is the text surrounding the entity where extraction records it
+/// (emails) and empty for other entity types.
+pub fn write_entities_csv(emails: &[Email], w: impl Write) -> csv::Result<()> {
+    write_entities_delimited(emails, w, b',')
+}
+
+/// Write one TSV row per extracted entity across a batch of emails
+///
+/// Same columns as [`write_entities_csv`].
+pub fn write_entities_tsv(emails: &[Email], w: impl Write) -> csv::Result<()> {
+    write_entities_delimited(emails, w, b'\t')
+}
+
+fn write_entities_delimited(emails: &[Email], w: impl Write, delimiter: u8) -> csv::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(w);
+
+    writer.write_record(["uid", "message_id", "entity_type", "value", "context"])?;
+
+    for email in emails {
+        let uid = email.uid.to_string();
+        let message_id = email.message_id.to_string();
+        let mut row = |entity_type: &str, value: &str, context: &str| {
+            writer.write_record([
+                uid.as_str(),
+                message_id.as_str(),
+                entity_type,
+                value,
+                context,
+            ])
+        };
+
+        let extracted = &email.extracted;
+        for e in &extracted.emails {
+            row("email", &e.address, &e.context)?;
+        }
+        for phone in &extracted.phone_numbers {
+            row("phone", &phone.normalized, "")?;
+        }
+        for url in &extracted.urls {
+            row("url", &url.url, "")?;
+        }
+        for name in &extracted.names {
+            row("name", name, "")?;
+        }
+        for company in &extracted.companies {
+            row("company", company, "")?;
+        }
+        for date in &extracted.dates {
+            row("date", date, "")?;
+        }
+        for amount in &extracted.amounts {
+            row("amount", &amount.value.to_string(), "")?;
+        }
+        for address in &extracted.addresses {
+            row("address", address, "")?;
+        }
+        for reference in &extracted.order_numbers {
+            row("order_number", &reference.value, "")?;
+        }
+        for quantity in &extracted.quantities {
+            row("quantity", &quantity.value.to_string(), "")?;
+        }
+        for time in &extracted.times {
+            let value = format!("{:02}:{:02}", time.hour, time.minute);
+            row("time", &value, "")?;
+        }
+        for ip in &extracted.ip_addresses {
+            row("ip", &ip.address.to_string(), "")?;
+        }
+        for item in &extracted.line_items {
+            row("line_item", &item.total.to_string(), "")?;
+        }
+        for hashtag in &extracted.hashtags {
+            row("hashtag", hashtag, "")?;
+        }
+        for handle in &extracted.social_handles {
+            row("social_handle", &handle.handle, "")?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
@@ -1,10 +1,12 @@
 //! Entity extraction from email content
 
+use crate::options::ExtractionOptions;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// All entities extracted from email content
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ExtractedEntities {
     /// Email addresses found in body
     pub emails: Vec<ExtractedEmail>,
@@ -32,23 +34,164 @@ pub struct ExtractedEntities {
 
     /// Social media handles
     pub social_handles: Vec<SocialHandle>,
+
+    /// Hashtags without the leading `#`, deduplicated case-insensitively
+    pub hashtags: Vec<String>,
+
+    /// Tracking pixel image URLs found in the HTML body
+    pub tracking_pixels: Vec<String>,
+
+    /// HTML links whose visible text shows a different domain than the href
+    pub misleading_links: Vec<MisleadingLink>,
+
+    /// Order, invoice, confirmation and ticket numbers
+    pub order_numbers: Vec<Reference>,
+
+    /// Item counts and measurements ("2 x Widget", "5 kg", "Qty: 10")
+    pub quantities: Vec<Quantity>,
+
+    /// Validated IPv4 and IPv6 addresses
+    pub ip_addresses: Vec<IpAddress>,
+
+    /// Receipt lines pairing a description with quantity and price
+    pub line_items: Vec<LineItem>,
+
+    /// Times of day ("3pm EST", "14:00 UTC")
+    pub times: Vec<TimeReference>,
+
+    /// Some entity list was cut to `ExtractionOptions::max_entities_per_type`
+    pub truncated: bool,
+}
+
+/// HTML anchor displaying one domain while linking to another
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MisleadingLink {
+    pub text: String,        // visible anchor text
+    pub href: String,        // actual link target
+    pub text_domain: String, // domain shown in the text (lowercase)
+    pub href_domain: String, // domain of the href (lowercase)
+}
+
+/// IP address found in text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IpAddress {
+    pub address: std::net::IpAddr,
+    pub is_private: bool, // private, loopback, link-local or otherwise non-routable
+}
+
+impl IpAddress {
+    /// Find every valid IP address in the text, in order, without duplicates
+    ///
+    /// Dotted numbers that are part of a longer sequence (`1.2.3.4.5`) or
+    /// prefixed like a version (`v1.2.3.4`) are skipped, as are colon runs
+    /// without a hex group and the unspecified address `::`.
+    #[must_use]
+    pub fn find_all(text: &str) -> Vec<Self> {
+        let mut found: Vec<Self> = Vec::new();
+
+        let candidates = IPV4_REGEX
+            .find_iter(text)
+            .chain(IPV6_REGEX.find_iter(text).filter(|m| {
+                // At least one hex group, and not part of a longer colon run
+                let before = text[..m.start()].chars().next_back();
+                let after = text[m.end()..].chars().next();
+                m.as_str().bytes().any(|b| b.is_ascii_hexdigit())
+                    && before != Some(':')
+                    && after != Some(':')
+            }))
+            .filter(|m| {
+                let before = text[..m.start()].chars().next_back();
+                let mut after = text[m.end()..].chars();
+                let glued_before = before.is_some_and(|c| c.is_alphanumeric() || c == '.');
+                let glued_after = match after.next() {
+                    Some('.') => after.next().is_some_and(|c| c.is_ascii_digit()),
+                    Some(c) => c.is_alphanumeric(),
+                    None => false,
+                };
+                !glued_before && !glued_after
+            });
+        let mut candidates: Vec<_> = candidates.collect();
+        candidates.sort_by_key(regex::Match::start);
+
+        for candidate in candidates {
+            let Ok(address) = candidate.as_str().parse::<std::net::IpAddr>() else {
+                continue;
+            };
+            if address.is_ipv6() && address.is_unspecified() {
+                continue;
+            }
+            if found.iter().all(|ip| ip.address != address) {
+                found.push(Self {
+                    address,
+                    is_private: !is_public_ip(address),
+                });
+            }
+        }
+
+        found
+    }
+
+    /// Check whether the address is globally routable
+    #[must_use]
+    pub const fn is_public(&self) -> bool {
+        !self.is_private
+    }
+}
+
+/// Quantity with optional unit and the item it counts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Quantity {
+    pub raw: String,
+    pub value: f64,
+    pub unit: Option<String>, // lowercase, e.g. "kg" or "items"
+    pub item: Option<String>, // e.g. "Widget" in "2 x Widget"
+}
+
+/// Time of day such as "3pm EST" or "14:00 UTC"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimeReference {
+    pub raw: String,
+    pub hour: u8, // 0-23
+    pub minute: u8,
+    pub timezone: Option<String>, // abbreviation or offset as written, e.g. "EST" or "UTC+2"
+}
+
+/// Order line such as "Widget x2 — $19.98"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LineItem {
+    pub description: String,
+    pub quantity: f64,           // 1 when the line states none
+    pub unit_price: Option<f64>, // stated or derived from total / quantity
+    pub total: f64,
+    pub currency: String, // ISO 4217 code, or "UNKNOWN"
+}
+
+/// Reference number introduced by a label such as "Order #" or "Invoice No."
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Reference {
+    pub value: String,
+    pub label: String,   // lowercase keyword, e.g. "order" or "invoice"
+    pub position: usize, // byte position of the value in body
 }
 
 /// Extracted email address
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExtractedEmail {
     pub address: String,
     pub context: String, // surrounding text
-    pub position: usize, // character position in body
+    pub position: usize, // character position in body (0 for HTML mailto links)
+    pub confidence: f32, // 0.0-1.0 from format and context cues
+    pub in_url: bool,    // found inside a URL (kept only with `keep_overlapping`)
 }
 
 /// Phone number with type detection
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PhoneNumber {
     pub raw: String,
     pub normalized: String,
     pub phone_type: PhoneType,
     pub country_code: Option<String>,
+    pub confidence: f32,
 }
 
 /// Type of phone number
@@ -61,12 +204,78 @@ pub enum PhoneType {
 }
 
 /// Extracted URL with analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExtractedUrl {
     pub url: String,
     pub domain: String,
+    pub is_secure: bool, // https scheme
     pub is_tracking: bool,
     pub url_type: UrlType,
+    pub confidence: f32,
+}
+
+/// Query parameters added by analytics and email platforms to track clicks
+const TRACKING_PARAMS: &[&str] = &[
+    "_hsenc",
+    "_hsmi",
+    "dclid",
+    "fbclid",
+    "gclid",
+    "gclsrc",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "mkt_tok",
+    "msclkid",
+    "oly_anon_id",
+    "oly_enc_id",
+    "twclid",
+    "vero_conv",
+    "vero_id",
+    "wickedid",
+    "yclid",
+];
+
+impl ExtractedUrl {
+    /// URL with tracking query parameters removed
+    ///
+    /// Drops `utm_*` parameters and the click identifiers in
+    /// `TRACKING_PARAMS` (`fbclid`, `gclid`, `mc_eid`, ...), keeping other
+    /// parameters in order along with the fragment. The `?` is dropped when
+    /// no parameter remains.
+    #[must_use]
+    pub fn cleaned(&self) -> String {
+        let (url, fragment) = self
+            .url
+            .split_once('#')
+            .map_or((self.url.as_str(), None), |(url, fragment)| {
+                (url, Some(fragment))
+            });
+        let Some((base, query)) = url.split_once('?') else {
+            return self.url.clone();
+        };
+
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|param| {
+                let key = param.split('=').next().unwrap_or_default().to_lowercase();
+                !key.is_empty()
+                    && !key.starts_with("utm_")
+                    && !TRACKING_PARAMS.contains(&key.as_str())
+            })
+            .collect();
+
+        let mut cleaned = base.to_string();
+        if !kept.is_empty() {
+            cleaned.push('?');
+            cleaned.push_str(&kept.join("&"));
+        }
+        if let Some(fragment) = fragment {
+            cleaned.push('#');
+            cleaned.push_str(fragment);
+        }
+        cleaned
+    }
 }
 
 /// Type of URL
@@ -78,22 +287,26 @@ pub enum UrlType {
     Tracking,
     Calendar,
     Document,
+    Survey,
     Other,
 }
 
 /// Monetary amount
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MonetaryAmount {
     pub raw: String,
     pub value: f64,
-    pub currency: String,
+    pub currency: String, // ISO 4217 code, or "UNKNOWN"
+    pub confidence: f32,
+    pub label: Option<String>, // nearest financial keyword before it, e.g. "total" or "refund"
 }
 
 /// Social media handle
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SocialHandle {
     pub platform: SocialPlatform,
     pub handle: String,
+    pub confidence: f32,
 }
 
 /// Social media platform
@@ -116,20 +329,131 @@ static PHONE_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
     Regex::new(r"(?:\+?1[-.\s]?)?(?:\(?\d{3}\)?[-.\s]?)?\d{3}[-.\s]?\d{4}").unwrap()
 });
 
+/// Numbers in international or non-NANP national notation, such as
+/// `+44 20 7946 0958` or `020 7946 0958`, used with a non-NANP
+/// `ExtractionOptions::default_region`
+static INTL_PHONE_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[-.\s]?|\b)\(?0?\d{1,4}\)?(?:[-.\s]?\d{2,4}){2,4}\b").unwrap()
+});
+
+/// Country calling codes by ISO 3166-1 alpha-2 region
+const CALLING_CODES: &[(&str, &str)] = &[
+    ("US", "1"),
+    ("CA", "1"),
+    ("GB", "44"),
+    ("IE", "353"),
+    ("FR", "33"),
+    ("DE", "49"),
+    ("AT", "43"),
+    ("CH", "41"),
+    ("NL", "31"),
+    ("BE", "32"),
+    ("LU", "352"),
+    ("ES", "34"),
+    ("PT", "351"),
+    ("IT", "39"),
+    ("DK", "45"),
+    ("SE", "46"),
+    ("NO", "47"),
+    ("FI", "358"),
+    ("PL", "48"),
+    ("CZ", "420"),
+    ("AU", "61"),
+    ("NZ", "64"),
+    ("IN", "91"),
+    ("JP", "81"),
+    ("SG", "65"),
+    ("ZA", "27"),
+    ("BR", "55"),
+    ("MX", "52"),
+];
+
 static URL_REGEX: std::sync::LazyLock<Regex> =
     std::sync::LazyLock::new(|| Regex::new(r"https?://[^\s<>\[\]{}|\\^]+").unwrap());
 
+/// ISO 4217 codes recognised before or after an amount
+const CURRENCY_CODES: &[&str] = &[
+    "USD", "EUR", "GBP", "CAD", "AUD", "NZD", "JPY", "CNY", "CHF", "SEK", "NOK", "DKK", "PLN",
+    "CZK", "HUF", "INR", "SGD", "HKD", "MXN", "BRL", "ZAR", "KRW", "TRY",
+];
+
+/// Currency symbols and their ISO 4217 codes, prefixed dollars first
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("CA$", "CAD"),
+    ("C$", "CAD"),
+    ("AU$", "AUD"),
+    ("A$", "AUD"),
+    ("NZ$", "NZD"),
+    ("HK$", "HKD"),
+    ("S$", "SGD"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₩", "KRW"),
+];
+
 static AMOUNT_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
-    Regex::new(r"(?:[$€£¥])\s*[\d,]+(?:\.\d{2})?|[\d,]+(?:\.\d{2})?\s*(?:USD|EUR|GBP|CAD|AUD)")
+    let codes = CURRENCY_CODES.join("|");
+    Regex::new(&format!(
+        r"(?:\b(?:US|CA|C|AU|A|NZ|HK|S))?[$€£¥₹₩]\s*[\d,]+(?:\.\d{{2}})?|[\d,]+(?:\.\d{{2}})?\s*(?:{codes})\b|\b(?:{codes})\s*[\d,]+(?:\.\d{{2}})?"
+    ))
+    .unwrap()
+});
+
+static REFERENCE_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(order|invoice|confirmation|reference|ref|ticket|booking|case|tracking)\b(?:\s*(?:number|no\.?|num\.?|nr\.?|id|code))?\s*[:#]?\s*#?\s*([a-z0-9][a-z0-9-]{2,}[a-z0-9])\b",
+    )
+    .unwrap()
+});
+
+static MULTIPLIER_QUANTITY_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"\b(\d{1,5})\s*[x×]\s+([A-Za-z][\w-]*(?:[ \t]+[A-Za-z][\w-]*){0,4})").unwrap()
+});
+
+static LABELLED_QUANTITY_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:qty|quantity|menge|cantidad|quantité)\b\.?\s*:?\s*(\d{1,6})\b").unwrap()
+});
+
+static UNIT_QUANTITY_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(\d{1,6}(?:[.,]\d{1,3})?)\s*(kg|g|lbs?|oz|l|ml|m|cm|mm|km|ft|pcs|pieces|items|units|boxes|pallets|packs|cartons|parcels|packages)\b(?:\s+of\s+([A-Za-z][\w-]*))?",
+    )
+    .unwrap()
+});
+
+static LINE_QUANTITY_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"(?i)\b(\d{1,4})\s*[x×](?:\s|$)|(?:^|\s)[x×]\s*(\d{1,4})\b|\b(?:qty|quantity)\.?\s*:?\s*(\d{1,4})\b")
         .unwrap()
 });
 
+/// 12-hour (`3pm`, `10:30 a.m.`) or 24-hour (`14:00`) times with an
+/// optional timezone abbreviation or UTC offset
+static TIME_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:(?P<h12>1[0-2]|0?[1-9])(?::(?P<m12>[0-5]\d))?\s?(?P<ampm>[ap])\.?m\b\.?|(?P<h24>[01]?\d|2[0-3]):(?P<m24>[0-5]\d)\b)(?:\s?(?P<tz>(?-i:(?:UTC|GMT)(?:\s?[+-]\d{1,2}(?::?\d{2})?)?|[+-]\d{2}:?\d{2}|[ECMP][SD]T|AK[SD]T|HST|BST|IST|CET|CEST|EET|EEST|WET|WEST|JST|KST|SGT|AEST|AEDT|ACST|AWST|NZST|NZDT)\b))?",
+    )
+    .unwrap()
+});
+
+static IPV4_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap());
+
+static IPV6_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"(?i)(?:[0-9a-f]{0,4}:){2,7}[0-9a-f]{0,4}").unwrap());
+
 static TWITTER_REGEX: std::sync::LazyLock<Regex> =
     std::sync::LazyLock::new(|| Regex::new(r"@([a-zA-Z0-9_]{1,15})").unwrap());
 
 static LINKEDIN_REGEX: std::sync::LazyLock<Regex> =
     std::sync::LazyLock::new(|| Regex::new(r"linkedin\.com/in/([a-zA-Z0-9-]+)").unwrap());
 
+static HASHTAG_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"#([\p{L}\p{N}_]+)").unwrap());
+
 /// Snap a byte index to the nearest valid UTF-8 char boundary (backwards)
 const fn snap_to_char_boundary(s: &str, idx: usize) -> usize {
     if idx >= s.len() {
@@ -142,100 +466,990 @@ const fn snap_to_char_boundary(s: &str, idx: usize) -> usize {
     i
 }
 
+/// Words marking a receipt line as a summary rather than an item
+const SUMMARY_KEYWORDS: &[&str] = &[
+    "total", "subtotal", "tax", "vat", "shipping", "delivery", "discount", "balance", "due",
+    "paid", "fee",
+];
+
+/// Keywords that make a nearby email address more trustworthy
+const EMAIL_KEYWORDS: &[&str] = &["email", "e-mail", "mail", "contact", "reach", "write"];
+
+/// Keywords that make a nearby phone number more trustworthy
+const PHONE_KEYWORDS: &[&str] = &[
+    "phone", "tel", "call", "mobile", "cell", "fax", "ph:", "ph.", "whatsapp",
+];
+
+/// Keywords that make a nearby amount more trustworthy
+const AMOUNT_KEYWORDS: &[&str] = &[
+    "total", "price", "amount", "cost", "due", "pay", "paid", "refund", "fee", "balance",
+    "invoice", "subtotal", "charge",
+];
+
+/// Check whether any keyword appears within `window` bytes before the span
+fn keyword_before(text: &str, start: usize, window: usize, keywords: &[&str]) -> bool {
+    let from = snap_to_char_boundary(text, start.saturating_sub(window));
+    let before = text[from..start].to_lowercase();
+    keywords.iter().any(|k| before.contains(k))
+}
+
+/// Keyword from the list ending closest to the span, within `window` bytes
+/// before it
+///
+/// On a tie the longer keyword wins, so "subtotal" is preferred to "total".
+fn nearest_keyword_before<'a>(
+    text: &str,
+    start: usize,
+    window: usize,
+    keywords: &[&'a str],
+) -> Option<&'a str> {
+    let from = snap_to_char_boundary(text, start.saturating_sub(window));
+    let before = text[from..start].to_lowercase();
+    keywords
+        .iter()
+        .filter_map(|k| before.rfind(k).map(|pos| (pos + k.len(), k.len(), *k)))
+        .max()
+        .map(|(_, _, keyword)| keyword)
+}
+
 impl ExtractedEntities {
     /// Extract all entities from text content
+    #[must_use]
     pub fn extract(text: &str) -> Self {
+        Self::extract_with_options(text, &ExtractionOptions::default())
+    }
+
+    /// Extract all entities from text content using custom options
+    #[must_use]
+    pub fn extract_with_options(text: &str, options: &ExtractionOptions) -> Self {
         let mut entities = Self::default();
 
-        // Extract emails
-        for cap in EMAIL_REGEX.find_iter(text) {
+        if options.extract_emails {
+            entities.emails = find_emails(text, options);
+        }
+        if options.extract_phones {
+            entities.phone_numbers = find_phone_numbers(text, options);
+        }
+        if options.extract_urls {
+            entities.urls = find_urls(text, url_regex(options));
+        }
+        if options.extract_amounts {
+            entities.amounts = find_amounts(text, options);
+        }
+        if options.extract_social {
+            entities.social_handles = find_social_handles(text);
+        }
+        if options.extract_hashtags {
+            entities.hashtags = find_hashtags(text, url_regex(options));
+        }
+        if options.extract_references {
+            entities.order_numbers = find_references(text);
+        }
+        if options.extract_quantities {
+            entities.quantities = find_quantities(text);
+        }
+        if options.extract_line_items {
+            entities.line_items = find_line_items(text);
+        }
+        if options.extract_times {
+            entities.times = find_times(text);
+        }
+        if options.extract_ips {
+            entities.ip_addresses = IpAddress::find_all(text);
+        }
+        if options.extract_companies {
+            entities.companies = find_companies(text, &options.company_suffixes);
+        }
+        if let Some(max) = options.max_entities_per_type {
+            entities.truncate(max);
+        }
+
+        entities
+    }
+
+    /// Keep at most `max` entities of each type, setting `truncated` when
+    /// anything was dropped
+    pub fn truncate(&mut self, max: usize) {
+        let truncated = cap(&mut self.emails, max)
+            | cap(&mut self.phone_numbers, max)
+            | cap(&mut self.urls, max)
+            | cap(&mut self.names, max)
+            | cap(&mut self.companies, max)
+            | cap(&mut self.dates, max)
+            | cap(&mut self.amounts, max)
+            | cap(&mut self.addresses, max)
+            | cap(&mut self.social_handles, max)
+            | cap(&mut self.hashtags, max)
+            | cap(&mut self.tracking_pixels, max)
+            | cap(&mut self.misleading_links, max)
+            | cap(&mut self.order_numbers, max)
+            | cap(&mut self.quantities, max)
+            | cap(&mut self.ip_addresses, max)
+            | cap(&mut self.line_items, max)
+            | cap(&mut self.times, max);
+        self.truncated |= truncated;
+    }
+
+    /// Check if any entities were extracted
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.emails.is_empty()
+            && self.phone_numbers.is_empty()
+            && self.urls.is_empty()
+            && self.amounts.is_empty()
+    }
+
+    /// Add the entities of another extraction (e.g. attachment text)
+    ///
+    /// Items already present are skipped: emails by case-insensitive
+    /// address, phones by normalized number, URLs by URL, amounts,
+    /// quantities and times by raw text, references by value, hashtags
+    /// case-insensitively, everything else by equality. Positions in the
+    /// merged items still refer to their own source text.
+    pub fn merge(&mut self, other: &Self) {
+        merge_unique(&mut self.emails, &other.emails, |e| {
+            e.address.to_lowercase()
+        });
+        merge_unique(&mut self.phone_numbers, &other.phone_numbers, |p| {
+            p.normalized.clone()
+        });
+        merge_unique(&mut self.urls, &other.urls, |u| u.url.clone());
+        merge_unique(&mut self.names, &other.names, Clone::clone);
+        merge_unique(&mut self.companies, &other.companies, Clone::clone);
+        merge_unique(&mut self.dates, &other.dates, Clone::clone);
+        merge_unique(&mut self.amounts, &other.amounts, |a| a.raw.clone());
+        merge_unique(&mut self.addresses, &other.addresses, Clone::clone);
+        merge_unique(&mut self.social_handles, &other.social_handles, |h| {
+            (h.platform.clone(), h.handle.clone())
+        });
+        merge_unique(&mut self.hashtags, &other.hashtags, |h| h.to_lowercase());
+        merge_unique(
+            &mut self.tracking_pixels,
+            &other.tracking_pixels,
+            Clone::clone,
+        );
+        merge_unique(
+            &mut self.misleading_links,
+            &other.misleading_links,
+            Clone::clone,
+        );
+        merge_unique(&mut self.order_numbers, &other.order_numbers, |r| {
+            r.value.clone()
+        });
+        merge_unique(&mut self.quantities, &other.quantities, |q| q.raw.clone());
+        merge_unique(&mut self.ip_addresses, &other.ip_addresses, |ip| ip.address);
+        merge_unique(&mut self.line_items, &other.line_items, Clone::clone);
+        merge_unique(&mut self.times, &other.times, |t| t.raw.clone());
+        self.truncated |= other.truncated;
+    }
+
+    /// Get count of all extracted entities
+    #[must_use]
+    pub const fn total_count(&self) -> usize {
+        self.emails.len()
+            + self.phone_numbers.len()
+            + self.urls.len()
+            + self.amounts.len()
+            + self.social_handles.len()
+    }
+}
+
+/// Bytes `EntityStream` buffers before extracting up to the last paragraph
+/// break
+const STREAM_SEGMENT_BYTES: usize = 64 * 1024;
+
+/// Entity extraction over a body that arrives in chunks
+///
+/// Chunks are buffered until 64 KiB of text is pending, which is then
+/// extracted up to its last paragraph break (or line break, or space), so
+/// the whole body is never held as one string. Entities do not span
+/// paragraphs, so `finish()` returns what
+/// [`ExtractedEntities::extract_with_options`] finds in the joined text,
+/// with positions in the joined text; only email contexts stop at a
+/// segment edge.
+#[derive(Debug)]
+pub struct EntityStream<'a> {
+    options: &'a ExtractionOptions,
+    pending: String,
+    offset: usize,
+    entities: ExtractedEntities,
+}
+
+impl<'a> EntityStream<'a> {
+    /// Start an extraction with the given options
+    #[must_use]
+    pub fn new(options: &'a ExtractionOptions) -> Self {
+        Self {
+            options,
+            pending: String::new(),
+            offset: 0,
+            entities: ExtractedEntities::default(),
+        }
+    }
+
+    /// Add the next chunk of text
+    pub fn push(&mut self, chunk: &str) {
+        self.pending.push_str(chunk);
+        if self.pending.len() < STREAM_SEGMENT_BYTES {
+            return;
+        }
+
+        let cut = self
+            .pending
+            .rfind("\n\n")
+            .map(|pos| pos + 2)
+            .or_else(|| self.pending.rfind('\n').map(|pos| pos + 1))
+            .or_else(|| self.pending.rfind(' ').map(|pos| pos + 1))
+            .unwrap_or(self.pending.len());
+        let rest = self.pending.split_off(cut);
+        let segment = std::mem::replace(&mut self.pending, rest);
+        self.extract_segment(&segment);
+    }
+
+    /// Extract the remaining text and return every entity found
+    #[must_use]
+    pub fn finish(mut self) -> ExtractedEntities {
+        let segment = std::mem::take(&mut self.pending);
+        self.extract_segment(&segment);
+        if let Some(max) = self.options.max_entities_per_type {
+            self.entities.truncate(max);
+        }
+        self.entities
+    }
+
+    fn extract_segment(&mut self, segment: &str) {
+        let mut found = ExtractedEntities::extract_with_options(segment, self.options);
+        for email in &mut found.emails {
+            email.position += self.offset;
+        }
+        for reference in &mut found.order_numbers {
+            reference.position += self.offset;
+        }
+        self.offset += segment.len();
+
+        let entities = &mut self.entities;
+        entities.emails.append(&mut found.emails);
+        entities.phone_numbers.append(&mut found.phone_numbers);
+        entities.urls.append(&mut found.urls);
+        entities.amounts.append(&mut found.amounts);
+        entities.social_handles.append(&mut found.social_handles);
+        entities.quantities.append(&mut found.quantities);
+        entities.line_items.append(&mut found.line_items);
+        entities.times.append(&mut found.times);
+        // Lists kept without duplicates in one text stay so across segments
+        for company in found.companies {
+            if !entities.companies.contains(&company) {
+                entities.companies.push(company);
+            }
+        }
+        for hashtag in found.hashtags {
+            if !entities
+                .hashtags
+                .iter()
+                .any(|h| h.eq_ignore_ascii_case(&hashtag))
+            {
+                entities.hashtags.push(hashtag);
+            }
+        }
+        for reference in found.order_numbers {
+            if !entities
+                .order_numbers
+                .iter()
+                .any(|r| r.value == reference.value)
+            {
+                entities.order_numbers.push(reference);
+            }
+        }
+        for ip in found.ip_addresses {
+            if entities
+                .ip_addresses
+                .iter()
+                .all(|i| i.address != ip.address)
+            {
+                entities.ip_addresses.push(ip);
+            }
+        }
+        entities.truncated |= found.truncated;
+    }
+}
+
+/// Extract only email addresses, with default options
+#[must_use]
+pub fn extract_emails(text: &str) -> Vec<ExtractedEmail> {
+    find_emails(text, &ExtractionOptions::default())
+}
+
+/// Extract only phone numbers, with default options
+#[must_use]
+pub fn extract_phone_numbers(text: &str) -> Vec<PhoneNumber> {
+    find_phone_numbers(text, &ExtractionOptions::default())
+}
+
+/// Extract only URLs
+#[must_use]
+pub fn extract_urls(text: &str) -> Vec<ExtractedUrl> {
+    find_urls(text, &URL_REGEX)
+}
+
+/// Extract only monetary amounts, with default options
+#[must_use]
+pub fn extract_amounts(text: &str) -> Vec<MonetaryAmount> {
+    find_amounts(text, &ExtractionOptions::default())
+}
+
+/// Truncate to `max` items, reporting whether any were dropped
+fn cap<T>(items: &mut Vec<T>, max: usize) -> bool {
+    let over = items.len() > max;
+    items.truncate(max);
+    over
+}
+
+/// Append clones of `from` items whose key is not yet in `into`
+fn merge_unique<T: Clone, K: PartialEq>(into: &mut Vec<T>, from: &[T], key: impl Fn(&T) -> K) {
+    for item in from {
+        let item_key = key(item);
+        if !into.iter().any(|existing| key(existing) == item_key) {
+            into.push(item.clone());
+        }
+    }
+}
+
+/// URL pattern from the options, or the built-in one
+fn url_regex(options: &ExtractionOptions) -> &Regex {
+    options.url_pattern.as_ref().unwrap_or(&URL_REGEX)
+}
+
+fn find_emails(text: &str, options: &ExtractionOptions) -> Vec<ExtractedEmail> {
+    let url_spans: Vec<(usize, usize)> = url_regex(options)
+        .find_iter(text)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+
+    options
+        .email_pattern
+        .as_ref()
+        .unwrap_or(&EMAIL_REGEX)
+        .find_iter(text)
+        .filter_map(|cap| {
+            let in_url = url_spans
+                .iter()
+                .any(|&(start, end)| start <= cap.start() && cap.end() <= end);
+            if in_url && !options.keep_overlapping {
+                return None;
+            }
+
             let start = snap_to_char_boundary(text, cap.start().saturating_sub(30));
             let end = snap_to_char_boundary(text, (cap.end() + 30).min(text.len()));
-            let context = text[start..end].to_string();
 
-            entities.emails.push(ExtractedEmail {
-                address: cap.as_str().to_string(),
-                context,
+            let address = cap.as_str();
+            let context = &text[start..end];
+            let mut confidence: f32 = 0.7;
+            if keyword_before(text, cap.start(), 30, EMAIL_KEYWORDS) {
+                confidence += 0.2;
+            }
+            // A mailto: target is an address by construction
+            let before = &text.as_bytes()[..cap.start()];
+            if before.len() >= 7 && before[before.len() - 7..].eq_ignore_ascii_case(b"mailto:") {
+                confidence = 1.0;
+            }
+            if address
+                .rsplit_once('.')
+                .is_some_and(|(_, tld)| tld.len() <= 6)
+            {
+                confidence += 0.1;
+            }
+
+            Some(ExtractedEmail {
+                address: address.to_string(),
+                context: context.to_string(),
                 position: cap.start(),
-            });
+                confidence: confidence.min(1.0),
+                in_url,
+            })
+        })
+        .collect()
+}
+
+fn find_phone_numbers(text: &str, options: &ExtractionOptions) -> Vec<PhoneNumber> {
+    let mut phone_numbers = Vec::new();
+
+    let region = options
+        .default_region
+        .as_deref()
+        .map(str::to_ascii_uppercase)
+        .and_then(|region| {
+            CALLING_CODES
+                .iter()
+                .find(|(code, _)| *code == region)
+                .map(|&(code, calling)| (code, calling))
+        });
+    let pattern = options.phone_pattern.as_ref().unwrap_or(match region {
+        Some((_, calling)) if calling != "1" => &INTL_PHONE_REGEX,
+        _ => &PHONE_REGEX,
+    });
+    for cap in pattern.find_iter(text) {
+        let raw = cap.as_str().to_string();
+        let (normalized, country_code) = match region {
+            Some((code, calling)) => {
+                let normalized = normalize_for_region(&normalize_phone(&raw), calling);
+                let country = country_for(&normalized, code).map(ToString::to_string);
+                (normalized, country)
+            }
+            None => (normalize_phone(&raw), None),
+        };
+
+        // Skip short digit runs and bare integers (order numbers, IDs)
+        let digit_count = raw.chars().filter(char::is_ascii_digit).count();
+        if digit_count < options.min_phone_digits || !has_phone_separator(&raw) {
+            continue;
         }
 
-        // Extract phone numbers
-        for cap in PHONE_REGEX.find_iter(text) {
-            let raw = cap.as_str().to_string();
-            let normalized = normalize_phone(&raw);
-            let phone_type = detect_phone_type(&normalized);
+        let phone_type = detect_phone_type(&normalized);
 
-            entities.phone_numbers.push(PhoneNumber {
-                raw,
-                normalized,
-                phone_type,
-                country_code: None,
-            });
+        let mut confidence: f32 = 0.5;
+        if raw.starts_with('+') || raw.starts_with('(') {
+            confidence += 0.1;
+        }
+        if raw.chars().filter(|c| !c.is_ascii_digit()).count() >= 2 {
+            confidence += 0.1;
+        }
+        if keyword_before(text, cap.start(), 30, PHONE_KEYWORDS) {
+            confidence += 0.3;
         }
 
-        // Extract URLs
-        for cap in URL_REGEX.find_iter(text) {
+        phone_numbers.push(PhoneNumber {
+            raw,
+            normalized,
+            phone_type,
+            country_code,
+            confidence: confidence.min(1.0),
+        });
+    }
+
+    phone_numbers
+}
+
+fn find_urls(text: &str, pattern: &Regex) -> Vec<ExtractedUrl> {
+    pattern
+        .find_iter(text)
+        .map(|cap| {
             let url = cap.as_str().to_string();
             let domain = extract_domain(&url);
+            let is_secure = url.starts_with("https://");
             let is_tracking = is_tracking_url(&url);
             let url_type = detect_url_type(&url, &domain);
 
-            entities.urls.push(ExtractedUrl {
+            let mut confidence: f32 = 0.7;
+            if is_secure {
+                confidence += 0.1;
+            }
+            if domain
+                .rsplit_once('.')
+                .is_some_and(|(_, tld)| tld.len() >= 2 && tld.chars().all(char::is_alphabetic))
+            {
+                confidence += 0.2;
+            }
+
+            ExtractedUrl {
                 url,
                 domain,
+                is_secure,
                 is_tracking,
                 url_type,
+                confidence: confidence.min(1.0),
+            }
+        })
+        .collect()
+}
+
+fn find_amounts(text: &str, options: &ExtractionOptions) -> Vec<MonetaryAmount> {
+    AMOUNT_REGEX
+        .find_iter(text)
+        .filter_map(|cap| {
+            let mut amount = parse_amount(cap.as_str())?;
+            amount.label = nearest_keyword_before(text, cap.start(), 30, AMOUNT_KEYWORDS)
+                .map(ToString::to_string);
+            if amount.label.is_some() {
+                amount.confidence = (amount.confidence + 0.3).min(1.0);
+            } else if options.require_amount_context {
+                return None;
+            }
+            Some(amount)
+        })
+        .collect()
+}
+
+fn find_social_handles(text: &str) -> Vec<SocialHandle> {
+    let mut social_handles = Vec::new();
+
+    for cap in TWITTER_REGEX.captures_iter(text) {
+        if let Some(handle) = cap.get(1) {
+            // An `@` glued to a word is usually part of an email address
+            let glued = text[..cap.get(0).map_or(0, |m| m.start())]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '.');
+            let mut confidence: f32 = if glued { 0.2 } else { 0.6 };
+            if keyword_before(text, handle.start(), 40, &["twitter", "x.com", "follow"]) {
+                confidence += 0.3;
+            }
+
+            social_handles.push(SocialHandle {
+                platform: SocialPlatform::Twitter,
+                handle: handle.as_str().to_string(),
+                confidence: confidence.min(1.0),
             });
         }
+    }
 
-        // Extract monetary amounts
-        for cap in AMOUNT_REGEX.find_iter(text) {
-            if let Some(amount) = parse_amount(cap.as_str()) {
-                entities.amounts.push(amount);
-            }
+    for cap in LINKEDIN_REGEX.captures_iter(text) {
+        if let Some(handle) = cap.get(1) {
+            social_handles.push(SocialHandle {
+                platform: SocialPlatform::LinkedIn,
+                handle: handle.as_str().to_string(),
+                confidence: 0.9,
+            });
         }
+    }
 
-        // Extract social handles
-        for cap in TWITTER_REGEX.captures_iter(text) {
-            if let Some(handle) = cap.get(1) {
-                entities.social_handles.push(SocialHandle {
-                    platform: SocialPlatform::Twitter,
-                    handle: handle.as_str().to_string(),
-                });
+    social_handles
+}
+
+/// `#word` tokens, skipping URL fragments, HTML entities, CSS hex colors
+/// and bare numbers ("order #123")
+fn find_hashtags(text: &str, url_pattern: &Regex) -> Vec<String> {
+    let url_spans: Vec<(usize, usize)> = url_pattern
+        .find_iter(text)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    let mut hashtags: Vec<String> = Vec::new();
+
+    for cap in HASHTAG_REGEX.captures_iter(text) {
+        let (Some(full), Some(tag)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        let glued = text[..full.start()]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '&' | '/' | '#'));
+        let in_url = url_spans
+            .iter()
+            .any(|&(start, end)| start <= full.start() && full.start() < end);
+        let tag = tag.as_str();
+        let is_hex_color =
+            matches!(tag.len(), 3 | 4 | 6 | 8) && tag.chars().all(|c| c.is_ascii_hexdigit());
+        let has_letter = tag.chars().any(char::is_alphabetic);
+
+        if glued || in_url || is_hex_color || !has_letter {
+            continue;
+        }
+        if !hashtags.iter().any(|h| h.eq_ignore_ascii_case(tag)) {
+            hashtags.push(tag.to_string());
+        }
+    }
+
+    hashtags
+}
+
+fn find_references(text: &str) -> Vec<Reference> {
+    let mut references: Vec<Reference> = Vec::new();
+
+    for cap in REFERENCE_REGEX.captures_iter(text) {
+        let (Some(label), Some(value)) = (cap.get(1), cap.get(2)) else {
+            continue;
+        };
+        // Words like "Order confirmed" carry no identifier
+        if !value.as_str().chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if references.iter().any(|r| r.value == value.as_str()) {
+            continue;
+        }
+
+        references.push(Reference {
+            value: value.as_str().to_string(),
+            label: label.as_str().to_lowercase(),
+            position: value.start(),
+        });
+    }
+
+    references
+}
+
+fn find_quantities(text: &str) -> Vec<Quantity> {
+    let mut found: Vec<(usize, usize, Quantity)> = Vec::new();
+
+    for cap in MULTIPLIER_QUANTITY_REGEX.captures_iter(text) {
+        let (Some(whole), Some(value)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        found.push((
+            whole.start(),
+            whole.end(),
+            Quantity {
+                raw: whole.as_str().to_string(),
+                value: value.as_str().parse().unwrap_or_default(),
+                unit: None,
+                item: cap.get(2).map(|m| m.as_str().trim().to_string()),
+            },
+        ));
+    }
+
+    for cap in LABELLED_QUANTITY_REGEX.captures_iter(text) {
+        let (Some(whole), Some(value)) = (cap.get(0), cap.get(1)) else {
+            continue;
+        };
+        // The item usually precedes the label on the same line
+        let line_start = text[..whole.start()].rfind('\n').map_or(0, |i| i + 1);
+        let item = text[line_start..whole.start()]
+            .trim()
+            .trim_end_matches([',', '-', '|', '(', ':'])
+            .trim();
+        found.push((
+            whole.start(),
+            whole.end(),
+            Quantity {
+                raw: whole.as_str().to_string(),
+                value: value.as_str().parse().unwrap_or_default(),
+                unit: None,
+                item: (!item.is_empty() && item.len() <= 40).then(|| item.to_string()),
+            },
+        ));
+    }
+
+    for cap in UNIT_QUANTITY_REGEX.captures_iter(text) {
+        let (Some(whole), Some(value), Some(unit)) = (cap.get(0), cap.get(1), cap.get(2)) else {
+            continue;
+        };
+        found.push((
+            whole.start(),
+            whole.end(),
+            Quantity {
+                raw: whole.as_str().to_string(),
+                value: value.as_str().replace(',', ".").parse().unwrap_or_default(),
+                unit: Some(unit.as_str().to_lowercase()),
+                item: cap.get(3).map(|m| m.as_str().to_string()),
+            },
+        ));
+    }
+
+    // Keep the earliest match where patterns overlap
+    found.sort_by_key(|(start, _, _)| *start);
+    let mut quantities = Vec::new();
+    let mut last_end = 0;
+    for (start, end, quantity) in found {
+        if start >= last_end {
+            quantities.push(quantity);
+            last_end = end;
+        }
+    }
+
+    quantities
+}
+
+fn find_times(text: &str) -> Vec<TimeReference> {
+    TIME_REGEX
+        .captures_iter(text)
+        .filter_map(|cap| {
+            let number = |name: &str| cap.name(name).and_then(|m| m.as_str().parse::<u8>().ok());
+            let (hour, minute) = if let Some(hour) = number("h12") {
+                let pm = cap
+                    .name("ampm")
+                    .is_some_and(|m| m.as_str().eq_ignore_ascii_case("p"));
+                // 12am is midnight, 12pm noon
+                (
+                    hour % 12 + if pm { 12 } else { 0 },
+                    number("m12").unwrap_or(0),
+                )
+            } else {
+                (number("h24")?, number("m24")?)
+            };
+
+            Some(TimeReference {
+                raw: cap.get(0)?.as_str().to_string(),
+                hour,
+                minute,
+                timezone: cap.name("tz").map(|m| m.as_str().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Best-effort receipt lines: a description, an optional quantity and one
+/// or two amounts on the same line
+///
+/// With two amounts the first is the unit price and the last the line
+/// total. A single amount is the total, unless marked as a unit price by
+/// `@`, "each" or "ea". Summary lines (total, tax, shipping, ...) and lines
+/// without a description are skipped.
+fn find_line_items(text: &str) -> Vec<LineItem> {
+    let mut items = Vec::new();
+
+    for line in text.lines() {
+        let amounts: Vec<_> = AMOUNT_REGEX.find_iter(line).collect();
+        let (Some(first), Some(last)) = (amounts.first(), amounts.last()) else {
+            continue;
+        };
+        let (Some(first_amount), Some(last_amount)) =
+            (parse_amount(first.as_str()), parse_amount(last.as_str()))
+        else {
+            continue;
+        };
+
+        let head = &line[..first.start()];
+        let head_lower = head.to_lowercase();
+        if SUMMARY_KEYWORDS.iter().any(|k| {
+            head_lower
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|w| w == *k)
+        }) {
+            continue;
+        }
+
+        let quantity_match = LINE_QUANTITY_REGEX.captures(line);
+        let mut quantity = quantity_match
+            .as_ref()
+            .and_then(|cap| cap.iter().skip(1).flatten().next())
+            .and_then(|m| m.as_str().parse::<f64>().ok())
+            .filter(|q| *q > 0.0);
+
+        // Description: text before the first amount, minus the quantity
+        let mut description = head.to_string();
+        if let Some(whole) = quantity_match.as_ref().and_then(|cap| cap.get(0))
+            && whole.end() <= first.start()
+        {
+            description.replace_range(whole.range(), " ");
+        }
+        let mut words: Vec<&str> = description
+            .trim()
+            .trim_matches(|c: char| matches!(c, '-' | '—' | '–' | ':' | '|' | '@' | '(' | ')'))
+            .split_whitespace()
+            .collect();
+        // Tabular receipts put a bare quantity column before unit price
+        if quantity.is_none()
+            && amounts.len() >= 2
+            && let Some(count) = words.last().and_then(|w| w.parse::<u16>().ok())
+            && count > 0
+        {
+            quantity = Some(f64::from(count));
+            words.pop();
+        }
+        let quantity = quantity.unwrap_or(1.0);
+        let description = words.join(" ");
+        if description.is_empty()
+            || description.len() > 80
+            || !description.chars().any(char::is_alphabetic)
+        {
+            continue;
+        }
+
+        let after_first = line[first.end()..].trim_start().to_lowercase();
+        let unit_marked = head.trim_end().ends_with('@')
+            || after_first.starts_with("each")
+            || after_first.starts_with("ea");
+        let (unit_price, total) = if amounts.len() >= 2 {
+            (Some(first_amount.value), last_amount.value)
+        } else if unit_marked {
+            (Some(first_amount.value), first_amount.value * quantity)
+        } else {
+            (Some(first_amount.value / quantity), first_amount.value)
+        };
+
+        items.push(LineItem {
+            description,
+            quantity,
+            unit_price,
+            total,
+            currency: last_amount.currency,
+        });
+    }
+
+    items
+}
+
+/// Check whether an address is globally routable
+fn is_public_ip(address: std::net::IpAddr) -> bool {
+    match address {
+        std::net::IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            // 100.64.0.0/10 is carrier-grade NAT space
+            let shared = a == 100 && (64..128).contains(&b);
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || shared)
+        }
+        std::net::IpAddr::V6(v6) => {
+            let documentation = v6.segments()[0] == 0x2001 && v6.segments()[1] == 0x0db8;
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_unique_local()
+                || v6.is_unicast_link_local()
+                || documentation)
+        }
+    }
+}
+
+/// Maximum number of capitalized words taken before a company suffix
+const MAX_COMPANY_NAME_WORDS: usize = 4;
+
+/// Capitalized words opening a sentence or greeting rather than a company
+/// name, dropped before a bare short legal form like `AG` or `SA`
+const COMPANY_NAME_STOPWORDS: &[&str] = &[
+    "The", "A", "An", "This", "That", "These", "Those", "Our", "Your", "My", "His", "Her", "Their",
+    "Its", "We", "You", "I", "It", "Team", "Dear", "Hi", "Hello", "Thanks", "Please", "All", "Any",
+    "Every", "Each", "No", "New", "And", "Or", "But", "If", "So", "See", "To", "From", "For",
+    "With", "At", "In", "On", "Of", "By", "Der", "Die", "Das", "Le", "La", "Les", "El",
+];
+
+fn find_companies(text: &str, suffixes: &[String]) -> Vec<String> {
+    let index = index_suffixes(suffixes);
+    let mut companies: Vec<String> = Vec::new();
+
+    for line in text.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let mut line_companies: Vec<(usize, String)> = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let key = words[i]
+                .trim_end_matches([',', ';', ')'])
+                .trim_end_matches('.');
+            let candidates = index.get(key).map_or(&[][..], Vec::as_slice);
+            let matched = candidates.iter().find(|suffix| match suffix.as_slice() {
+                [short] if is_short_form(short) => short_form_matches(short, words[i]),
+                _ => {
+                    words.len() - i >= suffix.len()
+                        && suffix
+                            .iter()
+                            .zip(&words[i..])
+                            .all(|(s, w)| suffix_word_matches(s, w))
+                }
+            });
+            let Some(suffix) = matched else {
+                i += 1;
+                continue;
+            };
+
+            let end = i + suffix.len();
+            let mut start = company_name_start(&words, i);
+            // "The AB" or "Team SA" are not companies; "Siemens AG" is
+            let bare_short_form = matches!(suffix.as_slice(), [short] if is_short_form(short) && !short.contains('.'));
+            while bare_short_form
+                && start < i
+                && COMPANY_NAME_STOPWORDS.contains(&words[start].trim_end_matches(','))
+            {
+                start += 1;
+            }
+            if start < i {
+                let name = words[start..end].join(" ");
+                let mut name = name.trim_end_matches([',', ';', ')']);
+                // The period after a bare form ends the sentence
+                if bare_short_form {
+                    name = name.trim_end_matches('.');
+                }
+                let name = name.to_string();
+                // "Big Corp Ltd" also ends in "Corp"; keep the longer name
+                match line_companies.last_mut() {
+                    Some((last_start, last)) if *last_start == start => *last = name,
+                    _ => line_companies.push((start, name)),
+                }
             }
+            i = end;
         }
 
-        for cap in LINKEDIN_REGEX.captures_iter(text) {
-            if let Some(handle) = cap.get(1) {
-                entities.social_handles.push(SocialHandle {
-                    platform: SocialPlatform::LinkedIn,
-                    handle: handle.as_str().to_string(),
-                });
+        for (_, name) in line_companies {
+            if !companies.contains(&name) {
+                companies.push(name);
             }
         }
+    }
 
-        entities
+    companies
+}
+
+/// Split the suffixes into words and index them by their first word, and
+/// by its all-caps spelling unless it is a short form
+///
+/// Candidates are sorted longest first so `GmbH & Co. KG` wins over `GmbH`.
+fn index_suffixes(suffixes: &[String]) -> HashMap<String, Vec<Vec<&str>>> {
+    let mut index: HashMap<String, Vec<Vec<&str>>> = HashMap::new();
+    for suffix in suffixes {
+        let words: Vec<&str> = suffix.split_whitespace().collect();
+        let Some(first) = words.first() else {
+            continue;
+        };
+        let key = first.trim_end_matches('.');
+        let mut keys = vec![key.to_string()];
+        if words.len() > 1 || !is_short_form(first) {
+            keys.push(key.to_uppercase());
+        }
+        for key in keys {
+            let candidates = index.entry(key).or_default();
+            if !candidates.contains(&words) {
+                candidates.push(words.clone());
+            }
+        }
     }
+    for candidates in index.values_mut() {
+        candidates.sort_by_key(|words| std::cmp::Reverse(words.len()));
+    }
+    index
+}
 
-    /// Check if any entities were extracted
-    #[must_use]
-    pub const fn is_empty(&self) -> bool {
-        self.emails.is_empty()
-            && self.phone_numbers.is_empty()
-            && self.urls.is_empty()
-            && self.amounts.is_empty()
+/// Compare a suffix word to a body word, ignoring trailing punctuation
+fn suffix_word_matches(suffix: &str, word: &str) -> bool {
+    let suffix = suffix.trim_end_matches('.');
+    let word = word.trim_end_matches([',', ';', ')']).trim_end_matches('.');
+    word == suffix || (word.eq_ignore_ascii_case(suffix) && !word.chars().any(char::is_lowercase))
+}
+
+/// Check for a legal form of at most two letters (`AG`, `SA`, `Co.`), which
+/// also occurs as an ordinary word or abbreviation
+fn is_short_form(suffix: &str) -> bool {
+    suffix.chars().filter(|c| c.is_alphabetic()).count() <= 2
+}
+
+/// Compare a short legal form exactly, with the same case and the dots of
+/// an abbreviation such as `S.A.` or `Co.` written out
+fn short_form_matches(suffix: &str, word: &str) -> bool {
+    let word = word.trim_end_matches([',', ';', ')']);
+    if suffix.contains('.') {
+        word == suffix || word.strip_suffix('.') == Some(suffix)
+    } else {
+        word.strip_suffix('.').unwrap_or(word) == suffix
     }
+}
 
-    /// Get count of all extracted entities
-    #[must_use]
-    pub const fn total_count(&self) -> usize {
-        self.emails.len()
-            + self.phone_numbers.len()
-            + self.urls.len()
-            + self.amounts.len()
-            + self.social_handles.len()
+/// Index of the first word of the name preceding a suffix at `suffix_start`
+fn company_name_start(words: &[&str], suffix_start: usize) -> usize {
+    let mut start = suffix_start;
+    while start > 0 && suffix_start - start < MAX_COMPANY_NAME_WORDS {
+        let word = words[start - 1];
+        // "Acme, Inc." keeps its comma; elsewhere punctuation ends the name
+        let adjacent = start == suffix_start;
+        let word = if adjacent {
+            word.trim_end_matches(',')
+        } else {
+            word
+        };
+        let capitalized = word
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_uppercase() || c.is_ascii_digit());
+        let connector = word == "&" && !adjacent;
+        if !(capitalized || connector) || word.ends_with([',', '.', ':', ';']) {
+            break;
+        }
+        start -= 1;
+    }
+    // A name never starts with a connector
+    while start < suffix_start && words[start] == "&" {
+        start += 1;
     }
+    start
 }
 
 fn normalize_phone(phone: &str) -> String {
@@ -245,11 +1459,71 @@ fn normalize_phone(phone: &str) -> String {
         .collect()
 }
 
+/// Rewrite a national number in E.164 form for the region's calling code
+///
+/// Numbers already starting with `+` are kept, a `00` international prefix
+/// becomes `+`, and a national trunk `0` is dropped (Italy keeps it). NANP
+/// numbers need 10 digits, or 11 with the leading `1`.
+fn normalize_for_region(normalized: &str, calling: &str) -> String {
+    if normalized.starts_with('+') {
+        return normalized.to_string();
+    }
+    if let Some(rest) = normalized.strip_prefix("00") {
+        return format!("+{rest}");
+    }
+    if calling == "1" {
+        return match normalized.len() {
+            10 => format!("+1{normalized}"),
+            11 if normalized.starts_with('1') => format!("+{normalized}"),
+            _ => normalized.to_string(),
+        };
+    }
+    let national = if calling == "39" {
+        normalized
+    } else {
+        normalized.strip_prefix('0').unwrap_or(normalized)
+    };
+    format!("+{calling}{national}")
+}
+
+/// Region of an E.164 number: the default region when its calling code
+/// matches, otherwise the first region using the longest matching code
+fn country_for<'a>(normalized: &str, default_region: &'a str) -> Option<&'a str> {
+    let digits = normalized.strip_prefix('+')?;
+    let default_calling = CALLING_CODES
+        .iter()
+        .find(|(code, _)| *code == default_region)
+        .map(|&(_, calling)| calling)?;
+    if digits.starts_with(default_calling) {
+        return Some(default_region);
+    }
+    CALLING_CODES
+        .iter()
+        .filter(|(_, calling)| digits.starts_with(calling))
+        .rev()
+        .max_by_key(|(_, calling)| calling.len())
+        .map(|&(code, _)| code)
+}
+
+fn has_phone_separator(raw: &str) -> bool {
+    raw.chars()
+        .any(|c| matches!(c, '+' | '-' | '.' | '(' | ')') || c.is_whitespace())
+}
+
 fn detect_phone_type(normalized: &str) -> PhoneType {
     let digits: String = normalized.chars().filter(char::is_ascii_digit).collect();
 
     if digits.starts_with("1800") || digits.starts_with("1888") || digits.starts_with("1877") {
         PhoneType::TollFree
+    } else if normalized.starts_with("+44") {
+        // UK numbering: 07 mobiles, 080 freephone
+        if digits.starts_with("447") {
+            PhoneType::Mobile
+        } else if digits.starts_with("4480") {
+            PhoneType::TollFree
+        } else {
+            PhoneType::Landline
+        }
     } else {
         PhoneType::Unknown
     }
@@ -274,6 +1548,38 @@ fn is_tracking_url(url: &str) -> bool {
         || lower.contains("trk")
 }
 
+/// Survey and feedback platforms, matched against the URL's domain
+const SURVEY_DOMAINS: &[&str] = &[
+    "surveymonkey.com",
+    "typeform.com",
+    "qualtrics.com",
+    "alchemer.com",
+    "surveygizmo.com",
+    "surveysparrow.com",
+    "delighted.com",
+    "getfeedback.com",
+    "medallia.com",
+    "asknice.ly",
+    "forms.gle",
+];
+
+/// URL fragments of survey pages and one-click NPS or rating links
+const SURVEY_URL_PATTERNS: &[&str] = &[
+    "docs.google.com/forms",
+    "forms.office.com",
+    "/survey",
+    "/nps/",
+    "nps_score=",
+    "rating=",
+];
+
+fn is_survey_url(lower_url: &str, domain: &str) -> bool {
+    SURVEY_DOMAINS
+        .iter()
+        .any(|d| domain == *d || domain.ends_with(&format!(".{d}")))
+        || SURVEY_URL_PATTERNS.iter().any(|p| lower_url.contains(p))
+}
+
 #[allow(clippy::case_sensitive_file_extension_comparisons)]
 fn detect_url_type(url: &str, domain: &str) -> UrlType {
     let lower = url.to_lowercase();
@@ -281,6 +1587,8 @@ fn detect_url_type(url: &str, domain: &str) -> UrlType {
 
     if lower.contains("unsubscribe") || lower.contains("optout") {
         UrlType::Unsubscribe
+    } else if is_survey_url(&lower, &domain_lower) {
+        UrlType::Survey
     } else if is_tracking_url(url) {
         UrlType::Tracking
     } else if domain_lower.contains("linkedin")
@@ -312,19 +1620,27 @@ fn parse_amount(raw: &str) -> Option<MonetaryAmount> {
 
     let value: f64 = clean.parse().ok()?;
 
-    let currency = if raw.contains('$') || raw.contains("USD") {
-        "USD"
-    } else if raw.contains('€') || raw.contains("EUR") {
-        "EUR"
-    } else if raw.contains('£') || raw.contains("GBP") {
-        "GBP"
-    } else {
-        "USD"
-    };
+    // Unrecognised currency context is reported rather than guessed
+    let currency = CURRENCY_CODES
+        .iter()
+        .copied()
+        .find(|code| raw.contains(code))
+        .or_else(|| {
+            CURRENCY_SYMBOLS
+                .iter()
+                .find(|(symbol, _)| raw.contains(symbol))
+                .map(|&(_, code)| code)
+        })
+        .unwrap_or("UNKNOWN");
+
+    // Explicit cents make a price more likely than a stray number
+    let confidence = if raw.contains('.') { 0.7 } else { 0.6 };
 
     Some(MonetaryAmount {
         raw: raw.to_string(),
         value,
         currency: currency.to_string(),
+        confidence,
+        label: None,
     })
 }
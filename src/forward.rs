@@ -0,0 +1,84 @@
+//! Detection of inline forwarded header blocks in body text
+
+use crate::mime;
+use crate::types::{EmailAddress, ForwardedHeaders};
+
+/// Find the first quoted header block of an inline forward
+///
+/// Recognises the blocks written by common clients, e.g. a `From:` line
+/// followed by `Sent:`/`Date:`, `To:`, `Cc:` and `Subject:` lines. Quote
+/// markers (`>`) and bold markers (`*`) are ignored. A lone `From:` line
+/// is not treated as a forward.
+pub fn detect_forwarded_headers(text: &str) -> Option<ForwardedHeaders> {
+    let lines: Vec<&str> = text.lines().map(clean_line).collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(value) = field_value(line, "from") else {
+            continue;
+        };
+        let Some(from) = parse_address(value) else {
+            continue;
+        };
+
+        let mut headers = ForwardedHeaders {
+            from: Some(from),
+            ..ForwardedHeaders::default()
+        };
+        let mut fields = 0;
+
+        for next in &lines[i + 1..] {
+            let Some((key, value)) = next.split_once(':') else {
+                break;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "to" => headers.to = parse_address_list(value),
+                "cc" => headers.cc = parse_address_list(value),
+                "subject" => headers.subject = Some(value.to_string()),
+                "date" | "sent" => headers.date = Some(value.to_string()),
+                _ => break,
+            }
+            fields += 1;
+        }
+
+        if fields > 0 {
+            return Some(headers);
+        }
+    }
+
+    None
+}
+
+/// Strip quote and emphasis markers around a body line
+fn clean_line(line: &str) -> &str {
+    line.trim_start_matches(|c: char| c == '>' || c.is_whitespace())
+        .trim_matches('*')
+        .trim()
+}
+
+/// Value of a `Key: value` line if the key matches
+fn field_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let (name, value) = line.split_once(':')?;
+    name.trim()
+        .trim_end_matches('*')
+        .eq_ignore_ascii_case(key)
+        .then(|| value.trim_start_matches('*').trim())
+}
+
+/// Parse one address, accepting Outlook's `Name [mailto:addr]` form
+fn parse_address(value: &str) -> Option<EmailAddress> {
+    let value = value.trim();
+    if let Some((name, rest)) = value.split_once("[mailto:") {
+        let address = rest.trim_end_matches(']').trim();
+        return EmailAddress::parse(&format!("{} <{address}>", name.trim()));
+    }
+    EmailAddress::parse(value)
+}
+
+/// Parse a comma- or semicolon-separated address list
+fn parse_address_list(value: &str) -> Vec<EmailAddress> {
+    mime::split_unquoted(&value.replace(';', ","), ',')
+        .iter()
+        .filter_map(|addr| parse_address(addr))
+        .collect()
+}
@@ -0,0 +1,479 @@
+//! HTML-aware helpers operating on raw HTML bodies
+
+use crate::extracted::MisleadingLink;
+use crate::options::HtmlOptions;
+use regex::Regex;
+use std::collections::HashMap;
+
+static IMG_TAG_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"(?is)<img\b[^>]*>").unwrap());
+
+static ANCHOR_TAG_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"(?is)<a\b[^>]*>").unwrap());
+
+static CONTAINER_TAG_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"(?is)<(div|span|p|td|table)\b[^>]*>").unwrap());
+
+static ATTR_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r#"(?s)([a-zA-Z][a-zA-Z0-9_:-]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+        .unwrap()
+});
+
+static DOMAIN_TEXT_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"(?i)^(?:https?://)?([a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,})(?:[:/?#]\S*)?$")
+        .unwrap()
+});
+
+static CSS_DIMENSION_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"(?i)(?:^|[;\s])(width|height)\s*:\s*(\d+)(?:px)?").unwrap()
+});
+
+/// Convert HTML to plain text
+///
+/// Drops tags along with `<script>` and `<style>` content, breaks lines
+/// after block elements, decodes common entities and removes blank lines.
+///
+/// ```rust
+/// use email_extract::html_to_text;
+///
+/// let text = html_to_text("<h1>Hello</h1><p>Tom &amp; Jerry</p>");
+/// assert_eq!(text, "Hello\nTom & Jerry");
+/// ```
+#[must_use]
+pub fn html_to_text(html: &str) -> String {
+    html_to_text_with_options(html, &HtmlOptions::default())
+}
+
+/// Convert HTML to plain text with custom block elements and cell separator
+///
+/// ```rust
+/// use email_extract::{HtmlOptions, html_to_text_with_options};
+///
+/// let options = HtmlOptions {
+///     cell_separator: " | ".into(),
+///     ..HtmlOptions::default()
+/// };
+/// let html = "<table><tr><td>Item</td><td>$5</td></tr><tr><td>Tax</td><td>$1</td></tr></table>";
+/// assert_eq!(html_to_text_with_options(html, &options), "Item | $5\nTax | $1");
+/// ```
+#[must_use]
+pub fn html_to_text_with_options(html: &str, options: &HtmlOptions) -> String {
+    html_to_text_mapped(html, options).0
+}
+
+/// Convert HTML to text, also returning for each byte of the text the byte
+/// offset in `html` it was produced from
+///
+/// Inserted line breaks and cell separators map to the tag that caused
+/// them, decoded entities to their `&`.
+pub fn html_to_text_mapped(html: &str, options: &HtmlOptions) -> (String, Vec<usize>) {
+    let mut result: Vec<(char, usize)> = Vec::new();
+    let mut in_tag = false;
+    let mut in_script = false;
+    let mut in_style = false;
+    let mut tag_start_idx: usize = 0;
+
+    let chars: Vec<(usize, char)> = html.char_indices().collect();
+    let lower_chars: Vec<char> = chars.iter().map(|(_, c)| c.to_ascii_lowercase()).collect();
+    let separator: Vec<char> = options.cell_separator.chars().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        if !in_tag && c == '<' {
+            tag_start_idx = i;
+            // Check for script/style start via a short char window
+            let remaining: String = lower_chars[i..].iter().take(9).collect();
+            if remaining.starts_with("<script") {
+                in_script = true;
+            } else if remaining.starts_with("<style") {
+                in_style = true;
+            } else if remaining.starts_with("</script") {
+                in_script = false;
+            } else if remaining.starts_with("</style") {
+                in_style = false;
+            }
+            in_tag = true;
+        } else if in_tag && c == '>' {
+            in_tag = false;
+            // Break lines around block elements, separate table cells
+            let name: String = lower_chars[tag_start_idx + 1..i]
+                .iter()
+                .skip_while(|c| **c == '/')
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect();
+            let closing = lower_chars.get(tag_start_idx + 1) == Some(&'/');
+            let tag_offset = chars[tag_start_idx].0;
+            if options.block_elements.contains(&name) {
+                if !separator.is_empty()
+                    && result.len() >= separator.len()
+                    && result[result.len() - separator.len()..]
+                        .iter()
+                        .map(|(c, _)| *c)
+                        .eq(separator.iter().copied())
+                {
+                    result.truncate(result.len() - separator.len());
+                }
+                result.push(('\n', tag_offset));
+            } else if closing && (name == "td" || name == "th") {
+                result.extend(separator.iter().map(|c| (*c, tag_offset)));
+            }
+        } else if !in_tag && !in_script && !in_style {
+            result.push((c, offset));
+        }
+        i += 1;
+    }
+
+    // Decode HTML entities (`&amp;` last so `&amp;lt;` stays `&lt;`)
+    for (entity, decoded) in [
+        ("&nbsp;", ' '),
+        ("&lt;", '<'),
+        ("&gt;", '>'),
+        ("&quot;", '"'),
+        ("&#39;", '\''),
+        ("&amp;", '&'),
+    ] {
+        result = replace_mapped(&result, entity, decoded);
+    }
+
+    // Clean up whitespace: trim lines and drop empty ones
+    let mut text = String::new();
+    let mut offsets = Vec::new();
+    for line in result.split(|(c, _)| *c == '\n') {
+        let start = line.iter().position(|(c, _)| !c.is_whitespace());
+        let end = line.iter().rposition(|(c, _)| !c.is_whitespace());
+        let (Some(start), Some(end)) = (start, end) else {
+            continue;
+        };
+        if !text.is_empty() {
+            text.push('\n');
+            offsets.push(line[start].1);
+        }
+        for &(c, offset) in &line[start..=end] {
+            text.push(c);
+            offsets.extend(std::iter::repeat_n(offset, c.len_utf8()));
+        }
+    }
+
+    (text, offsets)
+}
+
+/// `str::replace` over offset-tagged characters; the replacement takes the
+/// offset of the replaced pattern's first character
+fn replace_mapped(text: &[(char, usize)], pattern: &str, replacement: char) -> Vec<(char, usize)> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let mut out = Vec::with_capacity(text.len());
+    let mut i = 0;
+    while i < text.len() {
+        let matches = text.len() - i >= pattern.len()
+            && text[i..i + pattern.len()]
+                .iter()
+                .map(|(c, _)| *c)
+                .eq(pattern.iter().copied());
+        if matches {
+            out.push((replacement, text[i].1));
+            i += pattern.len();
+        } else {
+            out.push(text[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Parse the attributes of a single tag into a lowercase-keyed map
+pub fn tag_attributes(tag: &str) -> HashMap<String, String> {
+    ATTR_REGEX
+        .captures_iter(tag)
+        .filter_map(|cap| {
+            let key = cap.get(1)?.as_str().to_lowercase();
+            let value = cap.get(2).or_else(|| cap.get(3)).or_else(|| cap.get(4))?;
+            Some((key, value.as_str().to_string()))
+        })
+        .collect()
+}
+
+fn parse_dimension(value: &str) -> Option<u32> {
+    value.trim().trim_end_matches("px").trim().parse().ok()
+}
+
+/// Find image URLs that look like tracking pixels
+///
+/// Matches images of at most 1x1, zero-sized images and hidden images, using
+/// both the `width`/`height` attributes and inline CSS.
+pub fn tracking_pixels(html: &str) -> Vec<String> {
+    let mut pixels = Vec::new();
+
+    for tag in IMG_TAG_REGEX.find_iter(html) {
+        let attrs = tag_attributes(tag.as_str());
+        let Some(src) = attrs.get("src") else {
+            continue;
+        };
+
+        let mut width = attrs.get("width").and_then(|w| parse_dimension(w));
+        let mut height = attrs.get("height").and_then(|h| parse_dimension(h));
+        let style = attrs
+            .get("style")
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+
+        for cap in CSS_DIMENSION_REGEX.captures_iter(&style) {
+            let value = cap[2].parse().ok();
+            if cap[1].eq_ignore_ascii_case("width") {
+                width = width.or(value);
+            } else {
+                height = height.or(value);
+            }
+        }
+
+        let hidden = style.replace(' ', "").contains("display:none");
+        let tiny = matches!((width, height), (Some(w), Some(h)) if w <= 1 && h <= 1);
+        let zero = width == Some(0) || height == Some(0);
+
+        if hidden || tiny || zero {
+            pixels.push(src.clone());
+        }
+    }
+
+    pixels
+}
+
+/// Count images with a source, including tracking pixels
+pub fn image_count(html: &str) -> usize {
+    IMG_TAG_REGEX
+        .find_iter(html)
+        .filter(|tag| tag_attributes(tag.as_str()).contains_key("src"))
+        .count()
+}
+
+/// Collect addresses from `mailto:` links, which plain-text conversion drops
+pub fn mailto_targets(html: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+
+    for tag in ANCHOR_TAG_REGEX.find_iter(html) {
+        let attrs = tag_attributes(tag.as_str());
+        let Some(href) = attrs.get("href").map(|h| h.trim()) else {
+            continue;
+        };
+        let Some(scheme) = href.get(..7) else {
+            continue;
+        };
+        if !scheme.eq_ignore_ascii_case("mailto:") {
+            continue;
+        }
+
+        let recipients = href[7..].split('?').next().unwrap_or_default();
+        for address in recipients.replace("%40", "@").split(',') {
+            let address = address.trim();
+            if address.contains('@') && !targets.iter().any(|t| t.eq_ignore_ascii_case(address)) {
+                targets.push(address.to_string());
+            }
+        }
+    }
+
+    targets
+}
+
+/// Collect `http(s)` link targets, which plain-text conversion drops
+pub fn link_targets(html: &str) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+
+    for tag in ANCHOR_TAG_REGEX.find_iter(html) {
+        let attrs = tag_attributes(tag.as_str());
+        let Some(href) = attrs.get("href").map(|h| h.trim()) else {
+            continue;
+        };
+        if url_host(href).is_some() && !targets.iter().any(|t| t == href) {
+            targets.push(href.to_string());
+        }
+    }
+
+    targets
+}
+
+/// Split hidden preheader content out of an HTML body
+///
+/// Elements hidden with `display:none`, `visibility:hidden`, `mso-hide:all`,
+/// a zero `font-size`, `max-height` or `opacity` are removed from the
+/// returned HTML. The text of the first non-empty one is returned as the
+/// preheader.
+pub fn split_preheader(html: &str) -> (Option<String>, String) {
+    let (preheader, hidden) = hidden_elements(html);
+    (preheader, remove_ranges(html, &hidden))
+}
+
+fn remove_ranges(html: &str, ranges: &[(usize, usize)]) -> String {
+    let mut cleaned = String::with_capacity(html.len());
+    let mut copied_to = 0;
+    for &(start, end) in ranges {
+        cleaned.push_str(&html[copied_to..start]);
+        copied_to = end;
+    }
+    cleaned.push_str(&html[copied_to..]);
+    cleaned
+}
+
+/// Text of the first non-empty hidden element and the byte ranges of all
+/// hidden elements, in order
+fn hidden_elements(html: &str) -> (Option<String>, Vec<(usize, usize)>) {
+    let mut preheader = None;
+    let mut ranges = Vec::new();
+    let mut copied_to = 0;
+
+    for tag in CONTAINER_TAG_REGEX.captures_iter(html) {
+        let (Some(whole), Some(name)) = (tag.get(0), tag.get(1)) else {
+            continue;
+        };
+        if whole.start() < copied_to {
+            continue;
+        }
+        let hidden = tag_attributes(whole.as_str())
+            .get("style")
+            .is_some_and(|style| is_hidden_style(style));
+        if !hidden {
+            continue;
+        }
+        let Some((inner_end, end)) = find_closing_tag(html, whole.end(), name.as_str()) else {
+            continue;
+        };
+
+        if preheader.is_none() {
+            let text = html_to_text(&html[whole.end()..inner_end]);
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                preheader = Some(text);
+            }
+        }
+        ranges.push((whole.start(), end));
+        copied_to = end;
+    }
+
+    (preheader, ranges)
+}
+
+/// Visible text of an HTML body (as in `Body::text_from_html`) with the
+/// offset in `html` of each of its bytes
+pub fn visible_text_mapped(html: &str, options: &HtmlOptions) -> (String, Vec<usize>) {
+    let (_, hidden) = hidden_elements(html);
+    let (text, mut offsets) = html_to_text_mapped(&remove_ranges(html, &hidden), options);
+
+    // Shift offsets in the cleaned HTML past the hidden elements cut from it
+    for offset in &mut offsets {
+        for &(start, end) in &hidden {
+            if start <= *offset {
+                *offset += end - start;
+            } else {
+                break;
+            }
+        }
+    }
+
+    (text, offsets)
+}
+
+/// Check a CSS style attribute for declarations that hide an element
+fn is_hidden_style(style: &str) -> bool {
+    style.split(';').any(|declaration| {
+        let Some((property, value)) = declaration.split_once(':') else {
+            return false;
+        };
+        let property = property.trim().to_ascii_lowercase();
+        let value = value
+            .trim()
+            .trim_end_matches("!important")
+            .trim()
+            .to_ascii_lowercase();
+        let zero = value
+            .trim_end_matches(|c: char| c.is_ascii_alphabetic() || c == '%')
+            .parse::<f32>()
+            .is_ok_and(|v| v == 0.0);
+
+        match property.as_str() {
+            "display" => value == "none",
+            "visibility" => value == "hidden",
+            "mso-hide" => value == "all",
+            "font-size" | "max-height" | "opacity" => zero,
+            _ => false,
+        }
+    })
+}
+
+/// Find anchors whose visible text is a URL or domain other than the href's
+///
+/// Only `http(s)` links are checked. Domains are compared without a leading
+/// `www.`, and a subdomain of the displayed domain (or the reverse) counts
+/// as a match. Userinfo tricks like `https://bank.com@evil.com` resolve to
+/// the real host.
+pub fn misleading_links(html: &str) -> Vec<MisleadingLink> {
+    let mut links = Vec::new();
+
+    for tag in ANCHOR_TAG_REGEX.find_iter(html) {
+        let attrs = tag_attributes(tag.as_str());
+        let Some(href) = attrs.get("href").map(|h| h.trim()) else {
+            continue;
+        };
+        let Some(href_domain) = url_host(href) else {
+            continue;
+        };
+        let Some((inner_end, _)) = find_closing_tag(html, tag.end(), "a") else {
+            continue;
+        };
+
+        let text = html_to_text(&html[tag.end()..inner_end]);
+        let Some(text_domain) = DOMAIN_TEXT_REGEX
+            .captures(text.trim())
+            .map(|cap| cap[1].to_lowercase())
+        else {
+            continue;
+        };
+
+        let shown = text_domain.trim_start_matches("www.");
+        let actual = href_domain.trim_start_matches("www.");
+        let related = shown == actual
+            || shown.ends_with(&format!(".{actual}"))
+            || actual.ends_with(&format!(".{shown}"));
+        if !related {
+            links.push(MisleadingLink {
+                text: text.trim().to_string(),
+                href: href.to_string(),
+                text_domain,
+                href_domain,
+            });
+        }
+    }
+
+    links
+}
+
+/// Lowercase host of an `http(s)` URL, without userinfo or port
+fn url_host(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+/// Find the end of the element opened before `from`, honoring nesting
+///
+/// Returns the start and end offsets of the matching closing tag.
+fn find_closing_tag(html: &str, from: usize, name: &str) -> Option<(usize, usize)> {
+    let pattern = Regex::new(&format!(r"(?i)<(/?){name}\b[^>]*>")).ok()?;
+    let mut depth = 1;
+
+    for tag in pattern.captures_iter(&html[from..]) {
+        let whole = tag.get(0)?;
+        if tag.get(1).is_some_and(|slash| !slash.as_str().is_empty()) {
+            depth -= 1;
+            if depth == 0 {
+                return Some((from + whole.start(), from + whole.end()));
+            }
+        } else if !whole.as_str().ends_with("/>") {
+            depth += 1;
+        }
+    }
+
+    None
+}
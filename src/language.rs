@@ -0,0 +1,130 @@
+//! Stopword-based language identification and script direction
+
+use crate::types::TextDirection;
+
+/// Distinctive function words per ISO 639-1 code
+///
+/// Words shared by several of these languages (`de`, `en`, `que`, ...) are
+/// left out so that a hit counts for one language only.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "you", "of", "for", "with", "this", "that", "have", "we",
+            "your", "will", "not", "it", "be", "from", "please", "thank",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "les", "et", "est", "vous", "nous", "des", "une", "pour", "dans", "avec", "pas",
+            "qui", "sur", "du", "ce", "votre", "merci", "être", "mais",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "sie", "nicht", "mit", "ein", "eine", "für", "auf",
+            "wir", "ich", "zu", "den", "von", "bitte", "ihre", "sind",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "usted", "para", "con", "por", "del", "su", "muy", "gracias",
+            "está", "como", "pero", "esta", "nuestro", "hola", "sus", "al",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "gli", "della", "che", "è", "per", "una", "sono", "non", "di", "un", "grazie",
+            "questo", "siamo", "alla", "nel", "vostro", "anche", "ciao", "lo",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "os", "não", "uma", "com", "você", "do", "da", "em", "obrigado", "são", "seu", "sua",
+            "nós", "mais", "é", "ao", "pelo", "olá", "também", "muito",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "het", "een", "niet", "van", "voor", "met", "wij", "ik", "zijn", "op", "dat", "uw",
+            "bedankt", "ook", "aan", "te", "je", "naar", "wordt", "hebben",
+        ],
+    ),
+];
+
+/// Stopword hits a language needs before it is reported
+const MIN_HITS: u32 = 2;
+
+/// Candidate languages with their share of stopword hits, best first
+pub fn detect(text: &str) -> Vec<(String, f64)> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let hits: Vec<(&str, u32)> = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let count = words.iter().filter(|w| stopwords.contains(w)).count();
+            (*code, u32::try_from(count).unwrap_or(u32::MAX))
+        })
+        .filter(|&(_, count)| count >= MIN_HITS)
+        .collect();
+
+    let total: f64 = hits.iter().map(|&(_, count)| f64::from(count)).sum();
+    let mut candidates: Vec<(String, f64)> = hits
+        .into_iter()
+        .map(|(code, count)| (code.to_string(), f64::from(count) / total))
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    candidates
+}
+
+/// Explicit right-to-left marks and embeddings (RLM, ALM, RLE, RLO, RLI)
+const RTL_MARKS: &[char] = &['\u{200F}', '\u{061C}', '\u{202B}', '\u{202E}', '\u{2067}'];
+
+/// Explicit left-to-right marks and embeddings (LRM, LRE, LRO, LRI)
+const LTR_MARKS: &[char] = &['\u{200E}', '\u{202A}', '\u{202D}', '\u{2066}'];
+
+/// Share of strong characters, in percent, one direction needs to dominate
+const DOMINANT_DIRECTION_PERCENT: usize = 80;
+
+/// Hebrew, Arabic, Syriac, Thaana, N'Ko and their presentation forms
+const fn is_rtl_char(c: char) -> bool {
+    matches!(c, '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}')
+}
+
+/// Dominant writing direction of the text
+///
+/// A leading bidi mark or embedding decides on its own; otherwise letters
+/// of right-to-left scripts are counted against other letters. Text without
+/// letters is left-to-right.
+pub fn text_direction(text: &str) -> TextDirection {
+    match text.trim_start().chars().next() {
+        Some(c) if RTL_MARKS.contains(&c) => return TextDirection::Rtl,
+        Some(c) if LTR_MARKS.contains(&c) => return TextDirection::Ltr,
+        _ => {}
+    }
+
+    let (rtl, strong) = text
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .fold((0, 0), |(rtl, strong), c| {
+            (rtl + usize::from(is_rtl_char(c)), strong + 1)
+        });
+
+    if rtl * 100 >= strong * DOMINANT_DIRECTION_PERCENT && rtl > 0 {
+        TextDirection::Rtl
+    } else if (strong - rtl) * 100 >= strong * DOMINANT_DIRECTION_PERCENT {
+        TextDirection::Ltr
+    } else {
+        TextDirection::Mixed
+    }
+}
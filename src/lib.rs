@@ -19,7 +19,7 @@
 //! ```rust
 //! use email_extract::{Email, parse_email};
 //!
-//! let raw = b"From: alice@example.com\r\nSubject: Hello\r\n\r\nCall me at 555-1234";
+//! let raw = b"From: alice@example.com\r\nSubject: Hello\r\n\r\nCall me at 555-123-4567";
 //! let email = parse_email(1, raw).unwrap();
 //!
 //! assert_eq!(email.from.address, "alice@example.com");
@@ -27,12 +27,36 @@
 //! assert!(!email.extracted.phone_numbers.is_empty());
 //! ```
 
+mod calendar;
+mod dsn;
 mod error;
+#[cfg(feature = "csv")]
+mod export;
 mod extracted;
+mod forward;
+mod html;
+mod language;
+mod mime;
+mod options;
 mod parser;
+mod pgp;
+mod render;
 mod types;
+mod vcard;
 
 pub use error::{ParseError, Result};
+#[cfg(feature = "csv")]
+pub use export::{write_entities_csv, write_entities_tsv};
 pub use extracted::*;
-pub use parser::parse_email;
+pub use html::{html_to_text, html_to_text_with_options};
+pub use mime::decode_modified_utf7;
+pub use options::{
+    DEFAULT_BLOCK_ELEMENTS, DEFAULT_CLOSING_PHRASES, DEFAULT_COMPANY_SUFFIXES, DomainInfo,
+    ExtractionOptions, HtmlOptions, ParseOptions, SpamConfig, TRUSTED_SUPPRESSED_INDICATORS,
+    UrgencyConfig,
+};
+pub use parser::{
+    parse_email, parse_email_lossy, parse_email_lossy_with_options, parse_email_with_options,
+    parse_headers_only, parse_headers_only_with_options,
+};
 pub use types::*;
@@ -0,0 +1,290 @@
+//! MIME header parameters and charset decoding (RFC 2045, RFC 2047, RFC 2231)
+
+use base64::Engine;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+
+static ENCODED_WORD_REGEX: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"=\?([^?\s]+)\?([bBqQ])\?([^?\s]*)\?=").unwrap());
+
+/// One RFC 2231 section of a split parameter value
+struct Section {
+    index: u32,
+    encoded: bool,
+    value: String,
+}
+
+/// Parse a structured header value such as `attachment; filename="a.pdf"`
+///
+/// Returns the lowercase main value and the parameters keyed by lowercase
+/// name. RFC 2231 continuations (`name*0=`, `name*1*=`) and charset-tagged
+/// values (`name*=utf-8''%E2%82%AC.pdf`) are reassembled and decoded, and
+/// take precedence over a plain parameter of the same name.
+pub fn parse_params(value: &str) -> (String, BTreeMap<String, String>) {
+    let mut segments = split_unquoted(value, ';').into_iter();
+    let main = segments.next().unwrap_or_default().trim().to_lowercase();
+
+    let mut params = BTreeMap::new();
+    let mut extended: HashMap<String, Vec<Section>> = HashMap::new();
+
+    for segment in segments {
+        let Some((key, raw_value)) = segment.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = unquote(raw_value.trim());
+
+        match key.split_once('*') {
+            None => {
+                params.insert(key, value);
+            }
+            Some((name, section)) => {
+                let encoded = section.ends_with('*') || section.is_empty();
+                let index = section.trim_end_matches('*').parse().unwrap_or(0);
+                extended.entry(name.to_string()).or_default().push(Section {
+                    index,
+                    encoded,
+                    value,
+                });
+            }
+        }
+    }
+
+    for (name, mut sections) in extended {
+        sections.sort_by_key(|s| s.index);
+        params.insert(name, decode_sections(&sections));
+    }
+
+    (main, params)
+}
+
+/// Reassemble RFC 2231 sections, decoding percent-escapes with the charset
+/// declared on the first section
+fn decode_sections(sections: &[Section]) -> String {
+    let mut charset = String::new();
+    let mut bytes = Vec::new();
+
+    for (i, section) in sections.iter().enumerate() {
+        if !section.encoded {
+            bytes.extend_from_slice(section.value.as_bytes());
+            continue;
+        }
+
+        let mut value = section.value.as_str();
+        if i == 0 {
+            // charset'language'value
+            let mut parts = value.splitn(3, '\'');
+            if let (Some(cs), Some(_lang), Some(rest)) = (parts.next(), parts.next(), parts.next())
+            {
+                charset = cs.to_lowercase();
+                value = rest;
+            }
+        }
+        bytes.extend(percent_decode(value));
+    }
+
+    decode_charset_lossy(&bytes, &charset)
+}
+
+/// Decode `%XX` escapes (RFC 3986)
+pub fn percent_decode(value: &str) -> Vec<u8> {
+    unescape_hex(value, b'%')
+}
+
+/// Replace `<escape>XX` hex escapes with the bytes they encode
+fn unescape_hex(value: &str, escape: u8) -> Vec<u8> {
+    let raw = value.as_bytes();
+    let mut out = Vec::with_capacity(raw.len());
+    let mut i = 0;
+
+    while i < raw.len() {
+        if raw[i] == escape
+            && let Some(byte) = raw
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(raw[i]);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+/// Decode bytes in the given charset
+///
+/// Supports every label of the WHATWG Encoding Standard through
+/// `encoding_rs` (UTF-8, UTF-16, ISO-8859-*, windows-125*, KOI8-R/U,
+/// `Shift_JIS`, EUC-JP, ISO-2022-JP, GBK, GB18030, Big5, EUC-KR, ...) plus
+/// UTF-7 and IMAP modified UTF-7. An empty label is read as UTF-8. Returns
+/// `None` for unsupported charsets and malformed UTF-7.
+pub fn decode_charset(bytes: &[u8], charset: &str) -> Option<String> {
+    let label = charset.trim().trim_matches('"').to_ascii_lowercase();
+    match label.as_str() {
+        "" => Some(String::from_utf8_lossy(bytes).into_owned()),
+        "utf-7" | "utf7" | "unicode-1-1-utf-7" | "csunicode11utf7" => {
+            decode_utf7(std::str::from_utf8(bytes).ok()?)
+        }
+        "utf-7-imap" | "x-imap4-modified-utf7" => {
+            decode_modified_utf7(std::str::from_utf8(bytes).ok()?)
+        }
+        _ => {
+            let encoding = encoding_rs::Encoding::for_label_no_replacement(label.as_bytes())?;
+            Some(encoding.decode_without_bom_handling(bytes).0.into_owned())
+        }
+    }
+}
+
+/// Decode bytes, falling back to lossy UTF-8 when the charset is unsupported
+pub fn decode_charset_lossy(bytes: &[u8], charset: &str) -> String {
+    decode_charset(bytes, charset).unwrap_or_else(|| String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Decode UTF-7 text (RFC 2152)
+pub fn decode_utf7(text: &str) -> Option<String> {
+    decode_utf7_with(text, '+', '/')
+}
+
+/// Decode IMAP modified UTF-7, as used in mailbox names (RFC 3501 5.1.3)
+///
+/// ```rust
+/// use email_extract::decode_modified_utf7;
+///
+/// assert_eq!(decode_modified_utf7("Entw&APw-rfe").as_deref(), Some("Entwürfe"));
+/// ```
+#[must_use]
+pub fn decode_modified_utf7(text: &str) -> Option<String> {
+    decode_utf7_with(text, '&', ',')
+}
+
+/// Shared UTF-7 decoder; `shift` opens a base64 run using `slash` for 63
+fn decode_utf7_with(text: &str, shift: char, slash: char) -> Option<String> {
+    let sextet = |c: char| match c {
+        'A'..='Z' => Some(c as u32 - 'A' as u32),
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 26),
+        '0'..='9' => Some(c as u32 - '0' as u32 + 52),
+        '+' => Some(62),
+        c if c == slash => Some(63),
+        _ => None,
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != shift {
+            result.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'-') {
+            chars.next();
+            result.push(shift);
+            continue;
+        }
+
+        let mut units: Vec<u16> = Vec::new();
+        let (mut bits, mut bit_count) = (0u32, 0u32);
+        while let Some(value) = chars.peek().copied().and_then(sextet) {
+            chars.next();
+            bits = (bits << 6) | value;
+            bit_count += 6;
+            if bit_count >= 16 {
+                bit_count -= 16;
+                units.push(((bits >> bit_count) & 0xFFFF) as u16);
+            }
+        }
+        // The run ends at '-' (absorbed) or any other non-base64 character
+        if chars.peek() == Some(&'-') {
+            chars.next();
+        }
+        result.push_str(&String::from_utf16(&units).ok()?);
+    }
+
+    Some(result)
+}
+
+/// Decode RFC 2047 encoded words that the MIME parser left untouched
+///
+/// Words in unsupported charsets are kept as-is and reported in `warnings`.
+pub fn decode_encoded_words(value: &str, warnings: &mut Vec<String>) -> String {
+    if !value.contains("=?") {
+        return value.to_string();
+    }
+
+    ENCODED_WORD_REGEX
+        .replace_all(value, |cap: &regex::Captures| {
+            let charset = cap[1].split('*').next().unwrap_or_default();
+            let bytes = if cap[2].eq_ignore_ascii_case("b") {
+                base64::engine::general_purpose::STANDARD
+                    .decode(&cap[3])
+                    .ok()
+            } else {
+                Some(decode_q(&cap[3]))
+            };
+
+            bytes
+                .and_then(|bytes| decode_charset(&bytes, charset))
+                .unwrap_or_else(|| {
+                    let warning = format!("unsupported charset in encoded word: {charset}");
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                    cap[0].to_string()
+                })
+        })
+        .into_owned()
+}
+
+/// Decode the Q encoding of an encoded word (`_` is a space)
+fn decode_q(text: &str) -> Vec<u8> {
+    unescape_hex(&text.replace('_', " "), b'=')
+}
+
+/// Split on a delimiter, ignoring delimiters inside double quotes
+pub fn split_unquoted(value: &str, delimiter: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for c in value.chars() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && in_quotes {
+            escaped = true;
+        } else if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == delimiter && !in_quotes {
+            parts.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+    }
+    parts.push(current);
+
+    parts
+}
+
+/// Remove surrounding quotes and backslash escapes
+fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return value.to_string();
+    };
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                out.push(next);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,543 @@
+//! Configuration for email parsing
+
+use chrono::{DateTime, TimeDelta, Utc};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Options controlling how an email is parsed
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Timestamp used when the Date header is missing or unparseable
+    /// (defaults to `Utc::now()`)
+    pub now: Option<DateTime<Utc>>,
+
+    /// Server delivery time (e.g. IMAP INTERNALDATE), stored as
+    /// `Email::received_date` next to the sender-controlled Date header
+    pub internal_date: Option<DateTime<Utc>>,
+
+    /// Collapse runs of whitespace (including non-breaking spaces) in body
+    /// text to single spaces, keeping paragraph breaks, before counting and
+    /// extraction
+    pub normalize_whitespace: bool,
+
+    /// Keep the decoded plain text part verbatim in `Body::raw_text`, for
+    /// archiving; counts and extraction still use the cleaned text
+    pub preserve_formatting: bool,
+
+    /// Extract entities from both the plain text and the HTML part (its
+    /// text and link targets) and merge them, instead of from `best_text()`
+    /// only; positions of entities found outside `best_text()` refer to the
+    /// text they were found in
+    pub extract_all_parts: bool,
+
+    /// Entity extraction settings
+    pub extraction: ExtractionOptions,
+
+    /// HTML to text conversion settings
+    pub html: HtmlOptions,
+
+    /// Keywords that mark a message as urgent
+    pub urgency: UrgencyConfig,
+
+    /// Caller-supplied registration dates of sender domains
+    pub domain_info: DomainInfo,
+
+    /// Thresholds and weights of spam indicators
+    pub spam: SpamConfig,
+
+    /// Messages with at least this many visible (To and Cc) recipients are
+    /// treated as mailing list traffic even without list headers (defaults
+    /// to `None`, off)
+    pub mailing_list_recipients: Option<usize>,
+
+    /// Category hints below this confidence are dropped from
+    /// `EmailMetadata::category_hints` (defaults to 0.0, keeping all)
+    pub min_category_confidence: f32,
+}
+
+/// Domain registration data (e.g. from WHOIS) used to flag newly
+/// registered sender domains, since the crate itself does no lookups
+#[derive(Debug, Clone)]
+pub struct DomainInfo {
+    /// Registration date by lowercase domain
+    ///
+    /// A sender on a subdomain (`mail.example.com`) uses the closest listed
+    /// parent (`example.com`).
+    pub registered: HashMap<String, DateTime<Utc>>,
+
+    /// Domains registered less than this long before the message date are
+    /// considered new (defaults to 30 days)
+    pub new_domain_age: TimeDelta,
+}
+
+impl DomainInfo {
+    /// Registration date of the domain or its closest listed parent
+    #[must_use]
+    pub fn registered_at(&self, domain: &str) -> Option<DateTime<Utc>> {
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        let mut candidate = domain.as_str();
+        loop {
+            if let Some(date) = self.registered.get(candidate) {
+                return Some(*date);
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    }
+}
+
+impl Default for DomainInfo {
+    fn default() -> Self {
+        Self {
+            registered: HashMap::new(),
+            new_domain_age: TimeDelta::days(30),
+        }
+    }
+}
+
+/// Tuning of spam indicators whose sensitivity depends on the mail stream
+#[derive(Debug, Clone)]
+pub struct SpamConfig {
+    /// More tracking URLs than this raise `excessive_tracking` (defaults to 3)
+    pub tracking_threshold: usize,
+
+    /// Weight of `excessive_tracking` at one URL over the threshold, growing
+    /// in proportion to the tracking URL count (defaults to 0.2)
+    pub tracking_weight: f32,
+
+    /// Upper bound of the graduated `excessive_tracking` weight (defaults
+    /// to 0.6)
+    pub tracking_max_weight: f32,
+
+    /// Lowercase sender domains (and their subdomains) trusted not to be
+    /// spam, such as internal domains or the organization's own ESP
+    ///
+    /// For a trusted From domain the marketing and bulk indicators listed
+    /// in [`TRUSTED_SUPPRESSED_INDICATORS`] are dropped. Indicators of
+    /// spoofing or phishing (`multiple_from`, `messageid_domain_mismatch`,
+    /// `misleading_link`, ...) are kept, since a From domain can be forged,
+    /// but their weights are multiplied by `trusted_weight_factor`.
+    pub trusted_domains: HashSet<String>,
+
+    /// Weight multiplier for indicators kept for trusted senders (defaults
+    /// to 0.5)
+    pub trusted_weight_factor: f32,
+}
+
+/// Spam indicators dropped entirely for senders in
+/// `SpamConfig::trusted_domains`
+pub const TRUSTED_SUPPRESSED_INDICATORS: &[&str] = &[
+    "excessive_tracking",
+    "image_only",
+    "new_domain",
+    "noreply_sender",
+    "possible_recipient_leak",
+    "precedence_bulk",
+    "precedence_junk",
+    "tracking_pixel",
+    "urgency_language",
+];
+
+impl SpamConfig {
+    /// Check whether a sender domain or one of its parents is trusted
+    #[must_use]
+    pub fn is_trusted(&self, domain: &str) -> bool {
+        let domain = domain.trim().trim_end_matches('.').to_lowercase();
+        let mut candidate = domain.as_str();
+        loop {
+            if self.trusted_domains.contains(candidate) {
+                return true;
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Weight of `excessive_tracking` for a tracking URL count, if any
+    #[must_use]
+    pub fn tracking_weight_for(&self, count: usize) -> Option<f32> {
+        if count <= self.tracking_threshold {
+            return None;
+        }
+        let as_f32 = |n: usize| f32::from(u16::try_from(n).unwrap_or(u16::MAX));
+        let ratio = as_f32(count) / as_f32(self.tracking_threshold.saturating_add(1));
+        Some((self.tracking_weight * ratio).min(self.tracking_max_weight))
+    }
+}
+
+impl Default for SpamConfig {
+    fn default() -> Self {
+        Self {
+            tracking_threshold: 3,
+            tracking_weight: 0.2,
+            tracking_max_weight: 0.6,
+            trusted_domains: HashSet::new(),
+            trusted_weight_factor: 0.5,
+        }
+    }
+}
+
+/// Keywords that raise a message's urgency when found in the subject
+#[derive(Debug, Clone)]
+pub struct UrgencyConfig {
+    /// Lowercase keywords matched as whole words in the subject (defaults
+    /// to every built-in locale pack)
+    ///
+    /// The English pack holds only "urgent", "asap" and "emergency"; broader
+    /// words such as "immediately" or "critical" can be pushed here.
+    pub keywords: Vec<String>,
+}
+
+impl UrgencyConfig {
+    /// Locales with a built-in keyword pack
+    pub const LOCALES: &[&str] = &["en", "de", "es", "fr", "it", "pt", "nl"];
+
+    /// Build a config from the packs of the given locales (e.g. `["en", "de"]`)
+    ///
+    /// Unknown locales are ignored.
+    #[must_use]
+    pub fn with_locales(locales: &[&str]) -> Self {
+        let keywords = locales
+            .iter()
+            .flat_map(|locale| urgency_keywords(locale))
+            .map(ToString::to_string)
+            .collect();
+        Self { keywords }
+    }
+}
+
+impl Default for UrgencyConfig {
+    fn default() -> Self {
+        Self::with_locales(Self::LOCALES)
+    }
+}
+
+/// Built-in urgency keyword pack for a locale
+fn urgency_keywords(locale: &str) -> &'static [&'static str] {
+    match locale.to_ascii_lowercase().as_str() {
+        "en" => &["urgent", "asap", "emergency"],
+        "de" => &["dringend", "eilig", "sofort", "notfall", "umgehend"],
+        "es" => &["urgente", "inmediato", "emergencia", "cuanto antes"],
+        "fr" => &["urgent", "immédiat", "au plus vite", "dès que possible"],
+        "it" => &["urgente", "immediato", "emergenza", "al più presto"],
+        "pt" => &["urgente", "imediato", "emergência", "o mais rápido"],
+        "nl" => &["dringend", "spoed", "onmiddellijk", "zo snel mogelijk"],
+        _ => &[],
+    }
+}
+
+/// Options controlling HTML to text conversion
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// Lowercase tag names whose opening and closing tags start a new line
+    /// (defaults to [`DEFAULT_BLOCK_ELEMENTS`])
+    pub block_elements: Vec<String>,
+
+    /// Text inserted after each `<td>`/`<th>` cell (defaults to a tab)
+    pub cell_separator: String,
+
+    /// HTML bodies larger than this many bytes are not converted to text
+    /// (nor scanned for a preheader), leaving only the plain text part;
+    /// a warning is recorded (defaults to no limit)
+    pub max_size: Option<usize>,
+
+    /// Report entity positions (`ExtractedEmail::position`,
+    /// `Reference::position`) as byte offsets in the original HTML instead
+    /// of the stripped text when entities come from `text_from_html`, for
+    /// highlighting over the rendered HTML (defaults to false)
+    ///
+    /// When the extracted text no longer matches the HTML, as after
+    /// `ParseOptions::normalize_whitespace`, positions stay text offsets and
+    /// a warning is added to `Email::warnings`.
+    pub source_positions: bool,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            block_elements: DEFAULT_BLOCK_ELEMENTS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            cell_separator: "\t".into(),
+            max_size: None,
+            source_positions: false,
+        }
+    }
+}
+
+/// HTML elements that break lines by default
+pub const DEFAULT_BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "tbody",
+    "tfoot",
+    "thead",
+    "tr",
+    "ul",
+];
+
+/// Lowercase sign-off phrases recognised by `Body::closing()` by default
+pub const DEFAULT_CLOSING_PHRASES: &[&str] = &[
+    // English
+    "best regards",
+    "kind regards",
+    "warm regards",
+    "regards",
+    "best wishes",
+    "best",
+    "many thanks",
+    "thanks",
+    "thank you",
+    "cheers",
+    "sincerely",
+    "yours sincerely",
+    "yours truly",
+    "yours",
+    "take care",
+    // German
+    "mit freundlichen grüßen",
+    "freundliche grüße",
+    "viele grüße",
+    "beste grüße",
+    "liebe grüße",
+    "danke",
+    // French
+    "cordialement",
+    "bien cordialement",
+    "bien à vous",
+    "salutations",
+    "merci",
+    // Spanish
+    "saludos",
+    "un saludo",
+    "atentamente",
+    "gracias",
+    // Italian
+    "cordiali saluti",
+    "distinti saluti",
+    "saluti",
+    "grazie",
+    // Portuguese
+    "atenciosamente",
+    "abraços",
+    "obrigado",
+    "obrigada",
+    // Dutch
+    "met vriendelijke groet",
+    "vriendelijke groeten",
+    "groeten",
+    "bedankt",
+];
+
+/// Company legal-form suffixes recognised by default
+pub const DEFAULT_COMPANY_SUFFIXES: &[&str] = &[
+    // English-speaking jurisdictions
+    "Inc",
+    "Incorporated",
+    "LLC",
+    "L.L.C",
+    "Ltd",
+    "Limited",
+    "Corp",
+    "Corporation",
+    "Co.",
+    "PLC",
+    "LLP",
+    "LP",
+    "Pty Ltd",
+    "Pte Ltd",
+    // German-speaking
+    "GmbH",
+    "GmbH & Co. KG",
+    "AG",
+    "SE",
+    "KG",
+    "KGaA",
+    "UG",
+    "e.V",
+    // French-speaking
+    "SA",
+    "S.A",
+    "SARL",
+    "SAS",
+    "SASU",
+    "SNC",
+    // Italian, Spanish and Portuguese
+    "SpA",
+    "S.p.A",
+    "Srl",
+    "S.r.l",
+    "S.L",
+    "SL",
+    "Lda",
+    "Ltda",
+    // Benelux and Nordic
+    "BV",
+    "B.V",
+    "NV",
+    "N.V",
+    "AB",
+    "ASA",
+    "A/S",
+    "ApS",
+    "Oy",
+    "Oyj",
+    // Central and Eastern Europe
+    "Sp. z o.o",
+    "s.r.o",
+    "a.s",
+    "Kft",
+    "Zrt",
+];
+
+/// Options controlling entity extraction
+///
+/// Each `extract_*` flag enables one regex pass over the body; all are on by
+/// default.
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ExtractionOptions {
+    /// Extract email addresses
+    pub extract_emails: bool,
+
+    /// Extract phone numbers
+    pub extract_phones: bool,
+
+    /// Extract URLs
+    pub extract_urls: bool,
+
+    /// Extract monetary amounts
+    pub extract_amounts: bool,
+
+    /// Extract social media handles
+    pub extract_social: bool,
+
+    /// Extract `#hashtags`
+    pub extract_hashtags: bool,
+
+    /// Extract order, invoice and ticket numbers following a label word
+    pub extract_references: bool,
+
+    /// Extract item counts and measurements
+    pub extract_quantities: bool,
+
+    /// Extract IPv4 and IPv6 addresses
+    pub extract_ips: bool,
+
+    /// Extract receipt line items (description, quantity, prices)
+    pub extract_line_items: bool,
+
+    /// Extract times of day with their timezone
+    pub extract_times: bool,
+
+    /// Extract company names ending in a legal-form suffix
+    pub extract_companies: bool,
+
+    /// Legal-form suffixes that mark a company name, such as `Inc` or `GmbH`
+    /// (defaults to [`DEFAULT_COMPANY_SUFFIXES`])
+    ///
+    /// Matching ignores a trailing period and also accepts the all-caps
+    /// spelling. Multi-word suffixes like `GmbH & Co. KG` are supported.
+    /// Forms of one or two letters must match exactly: dotted ones like
+    /// `S.A` or `Co.` as written, bare ones like `AG` with the same case and
+    /// not after a sentence opener alone ("The AB").
+    pub company_suffixes: Vec<String>,
+
+    /// Keep email matches that lie inside a URL, such as
+    /// `https://host/u/john@example.com`, tagged with `in_url` instead of
+    /// dropping them
+    pub keep_overlapping: bool,
+
+    /// Drop monetary amounts that have no financial keyword such as
+    /// "total", "price" or "refund" shortly before them
+    pub require_amount_context: bool,
+
+    /// Minimum number of digits for a phone number match to be kept
+    /// (defaults to 10, a full NANP number)
+    pub min_phone_digits: usize,
+
+    /// Keep at most this many entities of each type, setting
+    /// `ExtractedEntities::truncated` when the limit is hit (defaults to
+    /// `None`, unlimited)
+    pub max_entities_per_type: Option<usize>,
+
+    /// ISO 3166-1 region (`"GB"`, `"DE"`, ...) assumed for phone numbers
+    /// without a `+` prefix (defaults to `None`)
+    ///
+    /// Numbers are then normalized to E.164 (`020 7946 0958` becomes
+    /// `+442079460958`) with `PhoneNumber::country_code` set, and national
+    /// formats of non-NANP regions are recognised.
+    pub default_region: Option<String>,
+
+    /// Email address pattern replacing the built-in one
+    pub email_pattern: Option<Regex>,
+
+    /// Phone number pattern replacing the built-in one; matches are still
+    /// subject to `min_phone_digits` and the separator check
+    pub phone_pattern: Option<Regex>,
+
+    /// URL pattern replacing the built-in one, also used to find email and
+    /// hashtag matches inside URLs
+    pub url_pattern: Option<Regex>,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            extract_emails: true,
+            extract_phones: true,
+            extract_urls: true,
+            extract_amounts: true,
+            extract_social: true,
+            extract_hashtags: true,
+            extract_references: true,
+            extract_quantities: true,
+            extract_ips: true,
+            extract_line_items: true,
+            extract_times: true,
+            extract_companies: true,
+            company_suffixes: DEFAULT_COMPANY_SUFFIXES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            keep_overlapping: false,
+            require_amount_context: false,
+            min_phone_digits: 10,
+            max_entities_per_type: None,
+            default_region: None,
+            email_pattern: None,
+            phone_pattern: None,
+            url_pattern: None,
+        }
+    }
+}
@@ -1,61 +1,548 @@
 //! Main email parser implementation
 
+use crate::calendar;
+use crate::dsn;
 use crate::error::{ParseError, Result};
-use crate::extracted::ExtractedEntities;
+use crate::extracted::{ExtractedEmail, ExtractedEntities};
+use crate::forward;
+use crate::html;
+use crate::mime;
+use crate::options::{HtmlOptions, ParseOptions, TRUSTED_SUPPRESSED_INDICATORS, UrgencyConfig};
+use crate::pgp;
 use crate::types::{
-    AuthResult, AuthenticationResults, Body, CategoryHint, Email, EmailAddress, EmailMetadata,
-    Headers, MessageId, Priority, Sentiment, SpamIndicator, Subject, ThreadInfo, Urgency,
+    Attachment, AuthResult, AuthenticationResults, AutoSubmitted, BimiInfo, Body, BodySource,
+    CampaignInfo, CampaignPlatform, CategoryHint, Email, EmailAddress, EmailMetadata, Headers,
+    MessageId, Precedence, Priority, SecurityInfo, Sentiment, SpamIndicator, StructureHealth,
+    StructureIssue, Subject, ThreadInfo, Urgency,
 };
-use chrono::{DateTime, Utc};
+use crate::vcard;
+use base64::Engine;
+use chrono::{DateTime, FixedOffset, Utc};
 use tracing::debug;
 
 /// Parse raw email bytes into a structured Email
 pub fn parse_email(uid: u32, raw: &[u8]) -> Result<Email> {
+    parse_email_with_options(uid, raw, &ParseOptions::default())
+}
+
+/// Parse raw email bytes into a structured Email using custom options
+pub fn parse_email_with_options(uid: u32, raw: &[u8], options: &ParseOptions) -> Result<Email> {
+    parse_email_at_depth(uid, raw, options, 0)
+}
+
+/// Parse raw email bytes, salvaging what it can from malformed messages
+///
+/// When the MIME structure cannot be parsed, the message is split at the
+/// first blank line instead: header lines that still look like
+/// `Name: value` are kept and everything after the blank line becomes the
+/// plain text body. The structure error is recorded in `Email::warnings`.
+/// Errors in the salvaged headers themselves, such as a missing From, are
+/// still returned.
+///
+/// Garbage before the headers, as left by a broken mailbox export, is
+/// skipped when a plausible header block (two or more `Name: value` lines
+/// including From, ending in a blank line) starts further down; the number
+/// of skipped bytes is recorded in `Email::warnings`.
+pub fn parse_email_lossy(uid: u32, raw: &[u8]) -> Result<Email> {
+    parse_email_lossy_with_options(uid, raw, &ParseOptions::default())
+}
+
+/// Parse raw email bytes with structure-error recovery using custom options
+pub fn parse_email_lossy_with_options(
+    uid: u32,
+    raw: &[u8],
+    options: &ParseOptions,
+) -> Result<Email> {
+    let skipped = header_block_start(raw);
+    let raw = &raw[skipped..];
+    let mut result = match parse_email_with_options(uid, raw, options) {
+        Err(ParseError::Structure(reason)) => salvage_email(uid, raw, options, &reason),
+        result => result,
+    };
+    if skipped > 0
+        && let Ok(ref mut email) = result
+    {
+        email.warnings.push(format!(
+            "{skipped} bytes of leading garbage before the headers skipped"
+        ));
+    }
+    result
+}
+
+/// Offset of the first plausible header block, 0 when the message starts
+/// with a header line or no block is found
+///
+/// Messages starting with a header line are left alone even without a
+/// From, so a forwarded header block in the body is never mistaken for
+/// the real one.
+fn header_block_start(raw: &[u8]) -> usize {
+    let first_line = raw.split(|&b| b == b'\n').next().unwrap_or_default();
+    if is_header_line(first_line.trim_ascii_end()) {
+        return 0;
+    }
+
+    let mut offset = 0;
+    for line in raw.split_inclusive(|&b| b == b'\n') {
+        if is_header_block(&raw[offset..]) {
+            return offset;
+        }
+        offset += line.len();
+    }
+    0
+}
+
+/// Check for two or more header lines, one of them From, up to a blank line
+fn is_header_block(block: &[u8]) -> bool {
+    let mut fields = 0;
+    let mut has_from = false;
+
+    for line in block.split_inclusive(|&b| b == b'\n') {
+        let line = line.trim_ascii_end();
+        if line.is_empty() {
+            return fields >= 2 && has_from;
+        }
+        if matches!(line.first(), Some(b' ' | b'\t')) && fields > 0 {
+            continue;
+        }
+        if !is_header_line(line) {
+            return false;
+        }
+        fields += 1;
+        has_from |= line.len() > 5 && line[..5].eq_ignore_ascii_case(b"from:");
+    }
+
+    false
+}
+
+/// `Name: value` line with a non-empty, space-free field name
+fn is_header_line(line: &[u8]) -> bool {
+    line.iter()
+        .position(|&b| b == b':')
+        .is_some_and(|colon| colon > 0 && line[..colon].iter().all(u8::is_ascii_graphic))
+}
+
+/// Maximum nesting of `message/rfc822` parts that are parsed
+const MAX_FORWARD_DEPTH: usize = 8;
+
+/// Parse only a header block (e.g. an IMAP `BODY[HEADER]` fetch)
+///
+/// Addresses, subject, date, thread and authentication data are parsed as
+/// usual; body, attachments and extracted entities are left empty, and
+/// metadata is derived from the headers alone.
+pub fn parse_headers_only(uid: u32, raw_headers: &[u8]) -> Result<Email> {
+    parse_headers_only_with_options(uid, raw_headers, &ParseOptions::default())
+}
+
+/// Parse only a header block using custom options
+pub fn parse_headers_only_with_options(
+    uid: u32,
+    raw_headers: &[u8],
+    options: &ParseOptions,
+) -> Result<Email> {
+    let (headers, _) =
+        mailparse::parse_headers(raw_headers).map_err(|e| ParseError::Structure(e.to_string()))?;
+
+    let mut email = parse_envelope(uid, &headers, options)?;
+    email.metadata = analyze_metadata(&email, options);
+
+    Ok(email)
+}
+
+fn parse_email_at_depth(
+    uid: u32,
+    raw: &[u8],
+    options: &ParseOptions,
+    depth: usize,
+) -> Result<Email> {
     let parsed = mailparse::parse_mail(raw).map_err(|e| ParseError::Structure(e.to_string()))?;
 
-    let headers = parse_headers(&parsed.headers)?;
-    let message_id = extract_message_id(&parsed.headers, uid);
-    let from = extract_from(&parsed.headers)?;
-    let to = extract_addresses(&parsed.headers, "to");
-    let cc = extract_addresses(&parsed.headers, "cc");
-    let bcc = extract_addresses(&parsed.headers, "bcc");
-    let reply_to = extract_reply_to(&parsed.headers);
-    let subject = extract_subject(&parsed.headers);
-    let date = extract_date(&parsed.headers);
-    let thread = extract_thread_info(&parsed.headers, &subject);
-    let body = extract_body(&parsed);
-
-    // Extract entities from body
-    let extracted = ExtractedEntities::extract(body.best_text());
+    let mut email = parse_envelope(uid, &parsed.headers, options)?;
+    let (body, forwarded_raw) = extract_body(&parsed, options, &mut email.warnings);
+
+    let forwarded = parse_forwarded(uid, &forwarded_raw, options, depth, &mut email.warnings);
+
+    let forwarded_headers = forward::detect_forwarded_headers(body.best_text());
+    let calendar = calendar::find_calendar_event(&parsed);
+    let vcards = vcard::find_vcards(&parsed);
+    let security = pgp::security_info(&parsed, &body.text);
+    let delivery_status = dsn::find_delivery_status(&parsed);
+
+    let extracted = extract_entities(&body, options, &mut email.warnings);
+
+    debug!(
+        "Parsed email: {} from {}",
+        email.subject.original, email.from.address
+    );
+
+    email.body = body;
+    email.extracted = extracted;
+    email.forwarded = forwarded;
+    email.forwarded_headers = forwarded_headers;
+    email.calendar = calendar;
+    email.vcards = vcards;
+    email.security = security;
+    email.delivery_status = delivery_status;
+    check_structure(&parsed, &mut email.structure.issues);
 
     // Analyze email metadata
-    let metadata = analyze_metadata(&from, &headers, &subject, &body, &extracted);
+    email.metadata = analyze_metadata(&email, options);
+
+    Ok(email)
+}
+
+/// Parse messages attached as `message/rfc822`
+///
+/// A part with an unreadable MIME structure is salvaged like a top-level
+/// lossy parse. Parts that still fail, and parts nested deeper than
+/// `MAX_FORWARD_DEPTH`, are dropped with a warning.
+fn parse_forwarded(
+    uid: u32,
+    forwarded_raw: &[Vec<u8>],
+    options: &ParseOptions,
+    depth: usize,
+    warnings: &mut Vec<String>,
+) -> Vec<Email> {
+    if forwarded_raw.is_empty() {
+        return Vec::new();
+    }
+    if depth >= MAX_FORWARD_DEPTH {
+        warnings.push(format!(
+            "{} forwarded messages nested deeper than {MAX_FORWARD_DEPTH} levels not parsed",
+            forwarded_raw.len()
+        ));
+        return Vec::new();
+    }
+
+    let mut forwarded = Vec::new();
+    for (index, inner) in forwarded_raw.iter().enumerate() {
+        let parsed = match parse_email_at_depth(uid, inner, options, depth + 1) {
+            Err(ParseError::Structure(reason)) => salvage_email(uid, inner, options, &reason),
+            result => result,
+        };
+        match parsed {
+            Ok(mut inner) => {
+                // The delivery time belongs to the outer message only
+                inner.received_date = None;
+                forwarded.push(inner);
+            }
+            Err(e) => warnings.push(format!("forwarded message {} skipped: {e}", index + 1)),
+        }
+    }
+    forwarded
+}
+
+/// Best-effort parse of a message whose MIME structure is unreadable
+fn salvage_email(uid: u32, raw: &[u8], options: &ParseOptions, reason: &str) -> Result<Email> {
+    let (header_block, body_bytes) = split_at_blank_line(raw);
+    let header_block = salvage_header_lines(header_block);
+    let (headers, _) = mailparse::parse_headers(&header_block)
+        .map_err(|e| ParseError::Structure(e.to_string()))?;
+
+    let mut email = parse_envelope(uid, &headers, options)?;
+    email.warnings.push(format!(
+        "unparseable MIME structure ({reason}), headers and body recovered by line scan"
+    ));
+
+    let text = String::from_utf8_lossy(body_bytes).into_owned();
+    email.body = build_body(
+        text,
+        None,
+        Vec::new(),
+        BodySource::Text,
+        options,
+        &mut email.warnings,
+    );
+    email.extracted = extract_entities(&email.body, options, &mut email.warnings);
+    email.metadata = analyze_metadata(&email, options);
+
+    Ok(email)
+}
+
+/// Split a message into header block and body at the first blank line
+fn split_at_blank_line(raw: &[u8]) -> (&[u8], &[u8]) {
+    let mut offset = 0;
+    for line in raw.split_inclusive(|&b| b == b'\n') {
+        let end = offset + line.len();
+        if line == b"\n" || line == b"\r\n" {
+            return (&raw[..offset], &raw[end..]);
+        }
+        offset = end;
+    }
+    (raw, &[])
+}
+
+/// Keep only `Name: value` lines (and their continuations) of a header block
+fn salvage_header_lines(block: &[u8]) -> Vec<u8> {
+    let mut cleaned = Vec::with_capacity(block.len() + 2);
+    let mut keep = false;
+
+    for line in block.split_inclusive(|&b| b == b'\n') {
+        let line = line.trim_ascii_end();
+        let is_continuation = matches!(line.first(), Some(b' ' | b'\t'));
+        if !is_continuation {
+            keep = is_header_line(line);
+        }
+        if keep {
+            cleaned.extend_from_slice(line);
+            cleaned.extend_from_slice(b"\r\n");
+        }
+    }
+
+    cleaned.extend_from_slice(b"\r\n");
+    cleaned
+}
+
+/// Extract entities from the body text, plus HTML-only signals
+fn extract_entities(
+    body: &Body,
+    options: &ParseOptions,
+    warnings: &mut Vec<String>,
+) -> ExtractedEntities {
+    // Inline PGP armor is base64 noise; only the cleartext is scanned
+    let text = pgp::strip_armor(body.text_without_disclaimer());
+    let mut extracted = if looks_like_text(&text) {
+        ExtractedEntities::extract_with_options(&text, &options.extraction)
+    } else {
+        warnings.push("body does not look like text, entity extraction skipped".into());
+        ExtractedEntities::default()
+    };
+    if let Some(ref html) = body.html {
+        if options.html.source_positions
+            && body.primary_source() == BodySource::HtmlStripped
+            && !map_positions_to_html(&mut extracted, &text, html, &options.html)
+        {
+            warnings.push(
+                "source positions not applied, entity positions are offsets in the stripped text"
+                    .into(),
+            );
+        }
+        extracted.tracking_pixels = html::tracking_pixels(html);
+        extracted.misleading_links = html::misleading_links(html);
+        if options.extraction.extract_emails {
+            promote_mailto_targets(&mut extracted, html);
+        }
+    }
+    if options.extract_all_parts {
+        merge_other_parts(&mut extracted, body, options);
+    }
+    // HTML-derived and merged entities count towards the cap too
+    if let Some(max) = options.extraction.max_entities_per_type {
+        extracted.truncate(max);
+    }
+    extracted
+}
+
+/// Add entities from the part `best_text()` is not derived from and from
+/// the HTML link targets
+fn merge_other_parts(extracted: &mut ExtractedEntities, body: &Body, options: &ParseOptions) {
+    let other = match body.primary_source() {
+        BodySource::Text => body.text_from_html.as_deref().unwrap_or_default(),
+        BodySource::HtmlStripped => &body.text,
+    };
+    let other = pgp::strip_armor(other);
+    if !other.is_empty() && looks_like_text(&other) {
+        extracted.merge(&ExtractedEntities::extract_with_options(
+            &other,
+            &options.extraction,
+        ));
+    }
+
+    if let Some(ref html) = body.html {
+        let links = html::link_targets(html).join("\n");
+        extracted.merge(&ExtractedEntities::extract_with_options(
+            &links,
+            &options.extraction,
+        ));
+    }
+}
+
+/// Rewrite entity positions in HTML-derived text as offsets in the HTML
+///
+/// Returns false, leaving positions unchanged, when the text is no longer a
+/// prefix of a fresh conversion, as after `normalize_whitespace` or PGP
+/// armor removal.
+fn map_positions_to_html(
+    extracted: &mut ExtractedEntities,
+    text: &str,
+    html: &str,
+    options: &HtmlOptions,
+) -> bool {
+    let (converted, offsets) = html::visible_text_mapped(html, options);
+    if !converted.starts_with(text) {
+        return false;
+    }
+    for email in &mut extracted.emails {
+        if let Some(&offset) = offsets.get(email.position) {
+            email.position = offset;
+        }
+    }
+    for reference in &mut extracted.order_numbers {
+        if let Some(&offset) = offsets.get(reference.position) {
+            reference.position = offset;
+        }
+    }
+    true
+}
+
+/// Quick check that decoded body text is not a binary or encrypted blob
+///
+/// Looks at the first `TEXT_SAMPLE_CHARS` characters: at most 10% may be
+/// control or replacement characters, and once there is enough text at
+/// most half of it may sit on encoded-looking lines, as in base64 or hex
+/// dumps. Text written without spaces (Chinese, Japanese, ...) passes.
+fn looks_like_text(text: &str) -> bool {
+    let sample = text
+        .char_indices()
+        .nth(TEXT_SAMPLE_CHARS)
+        .map_or(text, |(end, _)| &text[..end]);
+
+    let total = sample.chars().count();
+    let unprintable = sample
+        .chars()
+        .filter(|&c| !c.is_whitespace() && (c.is_control() || c == char::REPLACEMENT_CHARACTER))
+        .count();
+    if unprintable * 10 > total {
+        return false;
+    }
+
+    let encoded: usize = sample
+        .lines()
+        .map(str::trim)
+        .filter(|line| is_encoded_line(line))
+        .map(str::len)
+        .sum();
+    encoded < 1024 || encoded * 2 < total
+}
 
-    debug!("Parsed email: {} from {}", subject.original, from.address);
+/// Check for a long unbroken line of base64 or hex characters
+fn is_encoded_line(line: &str) -> bool {
+    line.len() >= 40
+        && line
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='))
+}
+
+/// Characters of body text inspected by `looks_like_text`
+const TEXT_SAMPLE_CHARS: usize = 4096;
+
+/// Build an email from its headers, with an empty body and no metadata
+fn parse_envelope(
+    uid: u32,
+    raw_headers: &[mailparse::MailHeader],
+    options: &ParseOptions,
+) -> Result<Email> {
+    let mut warnings = Vec::new();
+    let headers = parse_headers(raw_headers, &mut warnings)?;
+    let message_id = extract_message_id(raw_headers, uid);
+    let from_addresses = extract_from(raw_headers)?;
+    let from = from_addresses[0].clone();
+    let to = extract_addresses(raw_headers, "to");
+    let cc = extract_addresses(raw_headers, "cc");
+    let bcc = extract_addresses(raw_headers, "bcc");
+    let reply_to = extract_reply_to(raw_headers);
+    let subject = extract_subject(raw_headers, &mut warnings);
+    let original_date = extract_date(raw_headers);
+    let date = original_date.map_or_else(
+        || options.now.unwrap_or_else(Utc::now),
+        |dt| dt.with_timezone(&Utc),
+    );
+    let thread = extract_thread_info(raw_headers, &subject);
 
     Ok(Email {
         message_id,
         uid,
         from,
+        from_addresses,
         to,
         cc,
         bcc,
         reply_to,
         subject,
-        body,
+        body: Body::default(),
         date,
+        original_date,
+        received_date: options.internal_date,
         headers,
+        attachment_entities: Vec::new(),
         thread,
-        extracted,
-        metadata,
+        extracted: ExtractedEntities::default(),
+        forwarded: Vec::new(),
+        forwarded_headers: None,
+        calendar: None,
+        vcards: Vec::new(),
+        security: SecurityInfo::default(),
+        delivery_status: Vec::new(),
+        warnings,
+        structure: StructureHealth::default(),
+        metadata: EmailMetadata::default(),
     })
 }
 
+/// Walk the MIME tree collecting boundary and empty-part problems
+fn check_structure(parsed: &mailparse::ParsedMail, issues: &mut Vec<StructureIssue>) {
+    let mimetype = parsed.ctype.mimetype.to_lowercase();
+
+    if mimetype.starts_with("multipart/") {
+        match parsed.ctype.params.get("boundary") {
+            None => issues.push(StructureIssue::MissingBoundary),
+            Some(boundary) => {
+                let delimiter = format!("--{boundary}");
+                if !contains_bytes(parsed.raw_bytes, delimiter.as_bytes()) {
+                    issues.push(StructureIssue::BoundaryNotFound(boundary.clone()));
+                } else if !contains_bytes(parsed.raw_bytes, format!("{delimiter}--").as_bytes()) {
+                    issues.push(StructureIssue::UnterminatedMultipart(boundary.clone()));
+                }
+            }
+        }
+        if parsed.subparts.is_empty() {
+            issues.push(StructureIssue::MultipartWithoutParts);
+        }
+    } else if mimetype.starts_with("text/")
+        && parsed
+            .get_body_raw()
+            .is_ok_and(|body| body.iter().all(u8::is_ascii_whitespace))
+    {
+        issues.push(StructureIssue::EmptyTextPart);
+    }
+
+    for part in &parsed.subparts {
+        check_structure(part, issues);
+    }
+}
+
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+/// Add addresses from HTML `mailto:` links missing from the text matches
+fn promote_mailto_targets(extracted: &mut ExtractedEntities, html: &str) {
+    for address in html::mailto_targets(html) {
+        let known = extracted
+            .emails
+            .iter()
+            .any(|e| e.address.eq_ignore_ascii_case(&address));
+        if !known {
+            extracted.emails.push(ExtractedEmail {
+                context: format!("mailto:{address}"),
+                address,
+                position: 0,
+                confidence: 1.0,
+                in_url: false,
+            });
+        }
+    }
+}
+
 #[allow(clippy::unnecessary_wraps)]
-fn parse_headers(headers: &[mailparse::MailHeader]) -> Result<Headers> {
+fn parse_headers(headers: &[mailparse::MailHeader], warnings: &mut Vec<String>) -> Result<Headers> {
     let all: Vec<(String, String)> = headers
         .iter()
-        .map(|h| (h.get_key().to_lowercase(), h.get_value()))
+        .map(|h| {
+            let value = mime::decode_encoded_words(&h.get_value(), warnings);
+            (h.get_key().to_lowercase(), value)
+        })
         .collect();
 
     let content_type = headers
@@ -74,13 +561,56 @@ fn parse_headers(headers: &[mailparse::MailHeader]) -> Result<Headers> {
     let priority = headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == "x-priority")
-        .map(|h| Priority::from_header(&h.get_value()));
+        .map(|h| Priority::from_header(&h.get_value()))
+        .or_else(|| {
+            headers
+                .iter()
+                .find(|h| h.get_key().to_lowercase() == "importance")
+                .map(|h| Priority::from_importance(&h.get_value()))
+        });
 
     let list_unsubscribe = headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == "list-unsubscribe")
         .map(mailparse::MailHeader::get_value);
 
+    let precedence = headers
+        .iter()
+        .find(|h| h.get_key().to_lowercase() == "precedence")
+        .and_then(|h| Precedence::from_header(&h.get_value()));
+
+    let auto_submitted = headers
+        .iter()
+        .find(|h| h.get_key().eq_ignore_ascii_case("auto-submitted"))
+        .and_then(|h| AutoSubmitted::from_header(&h.get_value()));
+
+    let bimi = parse_bimi(headers);
+    let campaign = parse_campaign(headers);
+
+    let receipt_to = [
+        "disposition-notification-to",
+        "x-confirm-reading-to",
+        "return-receipt-to",
+    ]
+    .iter()
+    .find_map(|name| {
+        headers
+            .iter()
+            .find(|h| h.get_key().eq_ignore_ascii_case(name))
+            .and_then(|h| split_addresses(&h.get_value()).into_iter().next())
+    });
+
+    // One Delivered-To per local delivery hop, most recent first
+    let delivered_to = headers
+        .iter()
+        .filter(|h| h.get_key().eq_ignore_ascii_case("delivered-to"))
+        .flat_map(|h| split_addresses(&h.get_value()))
+        .collect();
+    let x_original_to = headers
+        .iter()
+        .find(|h| h.get_key().eq_ignore_ascii_case("x-original-to"))
+        .and_then(|h| split_addresses(&h.get_value()).into_iter().next());
+
     let authentication = parse_authentication_results(headers);
 
     let custom: Vec<(String, String)> = headers
@@ -95,11 +625,92 @@ fn parse_headers(headers: &[mailparse::MailHeader]) -> Result<Headers> {
         mailer,
         priority,
         list_unsubscribe,
+        precedence,
+        auto_submitted,
+        bimi,
+        campaign,
+        receipt_to,
+        delivered_to,
+        x_original_to,
         authentication,
         custom,
     })
 }
 
+/// Read campaign identifiers stamped by marketing automation platforms
+fn parse_campaign(headers: &[mailparse::MailHeader]) -> Option<CampaignInfo> {
+    let value = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            headers
+                .iter()
+                .find(|h| h.get_key().eq_ignore_ascii_case(name))
+                .map(|h| h.get_value().trim().to_string())
+                .filter(|v| !v.is_empty())
+        })
+    };
+    let has_prefix = |prefix: &str| {
+        headers
+            .iter()
+            .any(|h| h.get_key().to_ascii_lowercase().starts_with(prefix))
+    };
+
+    let feedback_id = value(&["feedback-id"]);
+    let platform = if has_prefix("x-mailchimp-") || has_prefix("x-mc-") {
+        Some(CampaignPlatform::Mailchimp)
+    } else if has_prefix("x-sg-") {
+        Some(CampaignPlatform::SendGrid)
+    } else if has_prefix("x-mailgun-") {
+        Some(CampaignPlatform::Mailgun)
+    } else if has_prefix("x-ses-")
+        || feedback_id
+            .as_deref()
+            .is_some_and(|id| id.to_ascii_lowercase().contains("amazonses"))
+    {
+        Some(CampaignPlatform::AmazonSes)
+    } else {
+        None
+    };
+
+    let campaign = CampaignInfo {
+        platform,
+        campaign_id: value(&[
+            "x-mailchimp-campaign",
+            "x-campaign-id",
+            "x-campaignid",
+            "x-mailgun-campaign-id",
+            "x-ses-configuration-set",
+        ]),
+        message_id: value(&["x-sg-eid", "x-sg-id", "x-mailgun-sid", "x-ses-message-id"]),
+        feedback_id,
+    };
+
+    (campaign != CampaignInfo::default()).then_some(campaign)
+}
+
+/// Read BIMI-Selector and BIMI-Location tag lists (`v=BIMI1; s=default;`)
+fn parse_bimi(headers: &[mailparse::MailHeader]) -> Option<BimiInfo> {
+    let tag = |name: &str, key: &str| {
+        let header = headers
+            .iter()
+            .find(|h| h.get_key().eq_ignore_ascii_case(name))?;
+        header
+            .get_value()
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let bimi = BimiInfo {
+        selector: tag("bimi-selector", "s"),
+        location: tag("bimi-location", "l"),
+        authority: tag("bimi-location", "a"),
+    };
+
+    (bimi != BimiInfo::default()).then_some(bimi)
+}
+
 fn parse_authentication_results(headers: &[mailparse::MailHeader]) -> AuthenticationResults {
     let mut results = AuthenticationResults::default();
 
@@ -140,32 +751,49 @@ fn extract_message_id(headers: &[mailparse::MailHeader], uid: u32) -> MessageId
         )
 }
 
-fn extract_from(headers: &[mailparse::MailHeader]) -> Result<EmailAddress> {
-    let from_header = headers
+/// Parse every mailbox across all From headers, in header order
+fn extract_from(headers: &[mailparse::MailHeader]) -> Result<Vec<EmailAddress>> {
+    let from_headers: Vec<String> = headers
         .iter()
-        .find(|h| h.get_key().to_lowercase() == "from")
+        .filter(|h| h.get_key().to_lowercase() == "from")
         .map(mailparse::MailHeader::get_value)
-        .ok_or_else(|| ParseError::MissingHeader("From".into()))?;
+        .collect();
 
-    EmailAddress::parse(&from_header).ok_or_else(|| ParseError::InvalidHeader {
-        header: "From".into(),
-        details: format!("Could not parse: {from_header}"),
-    })
+    let Some(first) = from_headers.first() else {
+        return Err(ParseError::MissingHeader("From".into()));
+    };
+
+    let addresses: Vec<EmailAddress> = from_headers
+        .iter()
+        .flat_map(|value| split_addresses(value))
+        .collect();
+
+    if addresses.is_empty() {
+        return Err(ParseError::InvalidHeader {
+            header: "From".into(),
+            details: format!("Could not parse: {first}"),
+        });
+    }
+
+    Ok(addresses)
 }
 
 fn extract_addresses(headers: &[mailparse::MailHeader], header_name: &str) -> Vec<EmailAddress> {
     headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == header_name)
-        .map(|h| {
-            h.get_value()
-                .split(',')
-                .filter_map(|addr| EmailAddress::parse(addr.trim()))
-                .collect()
-        })
+        .map(|h| split_addresses(&h.get_value()))
         .unwrap_or_default()
 }
 
+/// Split an address list on commas outside quoted display names
+fn split_addresses(value: &str) -> Vec<EmailAddress> {
+    mime::split_unquoted(value, ',')
+        .iter()
+        .filter_map(|addr| EmailAddress::parse(addr.trim()))
+        .collect()
+}
+
 fn extract_reply_to(headers: &[mailparse::MailHeader]) -> Option<EmailAddress> {
     headers
         .iter()
@@ -173,31 +801,29 @@ fn extract_reply_to(headers: &[mailparse::MailHeader]) -> Option<EmailAddress> {
         .and_then(|h| EmailAddress::parse(&h.get_value()))
 }
 
-fn extract_subject(headers: &[mailparse::MailHeader]) -> Subject {
-    let subject_text = headers
+fn extract_subject(headers: &[mailparse::MailHeader], warnings: &mut Vec<String>) -> Subject {
+    headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == "subject")
-        .map_or_else(
-            || "(no subject)".to_string(),
-            mailparse::MailHeader::get_value,
-        );
-
-    Subject::parse(&subject_text)
+        .map_or_else(Subject::missing, |h| {
+            Subject::parse(&mime::decode_encoded_words(&h.get_value(), warnings))
+        })
 }
 
-fn extract_date(headers: &[mailparse::MailHeader]) -> DateTime<Utc> {
+fn extract_date(headers: &[mailparse::MailHeader]) -> Option<DateTime<FixedOffset>> {
     headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == "date")
         .and_then(|h| DateTime::parse_from_rfc2822(&h.get_value()).ok())
-        .map_or_else(Utc::now, |dt| dt.with_timezone(&Utc))
 }
 
 fn extract_thread_info(headers: &[mailparse::MailHeader], subject: &Subject) -> ThreadInfo {
-    let in_reply_to = headers
+    let in_reply_to_all = headers
         .iter()
         .find(|h| h.get_key().to_lowercase() == "in-reply-to")
-        .map(|h| MessageId::new(h.get_value()));
+        .map(|h| split_message_ids(&h.get_value()))
+        .unwrap_or_default();
+    let in_reply_to = in_reply_to_all.last().cloned();
 
     let references: Vec<MessageId> = headers
         .iter()
@@ -210,6 +836,19 @@ fn extract_thread_info(headers: &[mailparse::MailHeader], subject: &Subject) ->
         })
         .unwrap_or_default();
 
+    let topic = headers
+        .iter()
+        .find(|h| h.get_key().to_lowercase() == "thread-topic")
+        .map(|h| h.get_value().trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    let thread_index = headers
+        .iter()
+        .find(|h| h.get_key().to_lowercase() == "thread-index")
+        .map(|h| h.get_value().trim().to_string())
+        .filter(|t| !t.is_empty());
+    let conversation_id = thread_index.as_deref().and_then(decode_thread_index);
+
     let is_reply = in_reply_to.is_some() || subject.reply_depth > 0;
     #[allow(clippy::cast_possible_truncation)]
     let thread_position = if is_reply {
@@ -220,152 +859,367 @@ fn extract_thread_info(headers: &[mailparse::MailHeader], subject: &Subject) ->
 
     ThreadInfo {
         in_reply_to,
+        in_reply_to_all,
         references,
         is_reply,
         thread_position,
+        topic,
+        thread_index,
+        conversation_id,
+    }
+}
+
+/// Message IDs of an In-Reply-To value
+///
+/// Takes every `<...>` pair, which skips comments like `(Jane's message
+/// of ...)`; values without brackets are split on whitespace instead.
+fn split_message_ids(value: &str) -> Vec<MessageId> {
+    let bracketed: Vec<MessageId> = value
+        .split('<')
+        .skip(1)
+        .filter_map(|part| part.split_once('>'))
+        .map(|(id, _)| MessageId::new(id))
+        .filter(|id| !id.as_str().is_empty())
+        .collect();
+    if !bracketed.is_empty() {
+        return bracketed;
     }
+    value.split_whitespace().map(MessageId::new).collect()
+}
+
+/// Extract the conversation GUID from an Exchange Thread-Index value
+///
+/// The decoded header block is 22 bytes: a reserved byte, five bytes of
+/// FILETIME and the 16-byte GUID, which is formatted like .NET's `Guid`
+/// (first three groups little-endian).
+fn decode_thread_index(value: &str) -> Option<String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.split_whitespace().collect::<String>())
+        .ok()?;
+    let guid = bytes.get(6..22)?;
+
+    Some(format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        guid[3],
+        guid[2],
+        guid[1],
+        guid[0],
+        guid[5],
+        guid[4],
+        guid[7],
+        guid[6],
+        guid[8],
+        guid[9],
+        guid[10],
+        guid[11],
+        guid[12],
+        guid[13],
+        guid[14],
+        guid[15],
+    ))
+}
+
+/// Build the body, also returning the raw bytes of attached messages
+fn extract_body(
+    parsed: &mailparse::ParsedMail,
+    options: &ParseOptions,
+    warnings: &mut Vec<String>,
+) -> (Body, Vec<Vec<u8>>) {
+    let (parts, preferred_source) = extract_body_parts(parsed);
+    let BodyParts {
+        text,
+        html,
+        attachments,
+        forwarded,
+        warnings: part_warnings,
+    } = parts;
+    warnings.extend(part_warnings);
+
+    let body = build_body(text, html, attachments, preferred_source, options, warnings);
+    (body, forwarded)
 }
 
-fn extract_body(parsed: &mailparse::ParsedMail) -> Body {
-    let (text, html) = extract_body_parts(parsed);
+/// Assemble a body from its decoded parts, deriving text, counts and signature
+fn build_body(
+    mut text: String,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+    preferred_source: BodySource,
+    options: &ParseOptions,
+    warnings: &mut Vec<String>,
+) -> Body {
+    // Oversized HTML is kept as-is but never converted to text
+    let convertible_html = html.as_deref().filter(|html| {
+        let too_large = options.html.max_size.is_some_and(|max| html.len() > max);
+        if too_large {
+            warnings.push(format!(
+                "HTML body of {} bytes exceeds the size limit, not converted to text",
+                html.len()
+            ));
+        }
+        !too_large
+    });
+
+    // Hidden preview text is kept apart from the visible HTML text
+    let (preheader, visible_html) = convertible_html.map_or((None, None), |html| {
+        let (preheader, visible) = html::split_preheader(html);
+        (preheader, Some(visible))
+    });
 
-    // Extract text from HTML if no plain text
-    let text_from_html = if text.is_empty() {
-        html.as_ref().map(|h| strip_html(h))
+    // Extract text from HTML if no plain text or HTML is the preferred part,
+    // or when both parts are scanned for entities
+    let mut text_from_html = if text.is_empty()
+        || preferred_source == BodySource::HtmlStripped
+        || options.extract_all_parts
+    {
+        visible_html
+            .as_deref()
+            .map(|html| html::html_to_text_with_options(html, &options.html))
     } else {
         None
     };
 
-    let best_text = if !text.is_empty() {
-        &text
-    } else if let Some(ref html_text) = text_from_html {
-        html_text
-    } else {
-        ""
-    };
+    let raw_text = (options.preserve_formatting && !text.is_empty()).then(|| text.clone());
 
-    // Separate signature from content
-    let (content_without_signature, signature) = separate_signature(best_text);
+    if options.normalize_whitespace {
+        text = collapse_whitespace(&text);
+        text_from_html = text_from_html.as_deref().map(collapse_whitespace);
+    }
 
-    Body {
-        word_count: best_text.split_whitespace().count(),
-        char_count: best_text.len(),
-        line_count: best_text.lines().count(),
+    let mut body = Body {
         text,
+        raw_text,
         html,
         text_from_html,
-        language: None,         // Could add language detection
-        has_attachments: false, // Would need to check multipart structure
-        signature,
-        content_without_signature,
+        preheader,
+        preferred_source,
+        word_count: 0,
+        char_count: 0,
+        line_count: 0,
+        language: None,
+        has_attachments: !attachments.is_empty(),
+        attachments,
+        signature: None,
+        disclaimer: None,
+        content_without_signature: String::new(),
+    };
+
+    // Legal boilerplate is kept aside and left out of the counts
+    let (text, disclaimer) = separate_disclaimer(body.best_text());
+    let word_count = text.split_whitespace().count();
+    let char_count = text.len();
+    let line_count = text.lines().count();
+
+    // Separate signature from content
+    let (content_without_signature, signature) = separate_signature(text);
+
+    body.word_count = word_count;
+    body.char_count = char_count;
+    body.line_count = line_count;
+    body.signature = signature;
+    body.disclaimer = disclaimer;
+    body.content_without_signature = content_without_signature;
+    // Detected after the split so a signature or disclaimer in another
+    // language does not skew it
+    body.language = body
+        .detected_languages()
+        .into_iter()
+        .next()
+        .map(|(code, _)| code);
+    body
+}
+
+/// Collapse whitespace runs within lines to single spaces and blank-line
+/// runs to a single paragraph break
+fn collapse_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut paragraph_break = false;
+
+    for line in text.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            paragraph_break = !result.is_empty();
+            continue;
+        }
+
+        if !result.is_empty() {
+            result.push('\n');
+            if paragraph_break {
+                result.push('\n');
+            }
+        }
+        paragraph_break = false;
+        result.push_str(&words.join(" "));
+    }
+
+    result
+}
+
+/// Content collected while walking the MIME tree
+#[derive(Default)]
+struct BodyParts {
+    text: String,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+    forwarded: Vec<Vec<u8>>,
+    warnings: Vec<String>,
+}
+
+impl BodyParts {
+    /// Decode a leaf part in its declared charset
+    ///
+    /// Unsupported charsets are decoded as lossy UTF-8 and recorded as a
+    /// warning.
+    fn decode(&mut self, part: &mailparse::ParsedMail) -> Option<String> {
+        let raw = part.get_body_raw().ok()?;
+        let charset = &part.ctype.charset;
+        Some(mime::decode_charset(&raw, charset).unwrap_or_else(|| {
+            let warning = format!("unsupported charset {charset}, decoded as UTF-8");
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
+            }
+            String::from_utf8_lossy(&raw).into_owned()
+        }))
     }
 }
 
-fn extract_body_parts(parsed: &mailparse::ParsedMail) -> (String, Option<String>) {
-    let mut text = String::new();
-    let mut html: Option<String> = None;
+fn extract_body_parts(parsed: &mailparse::ParsedMail) -> (BodyParts, BodySource) {
+    let mut parts = BodyParts::default();
+    let mut preferred = None;
 
     if parsed.subparts.is_empty() {
         let content_type = parsed.ctype.mimetype.to_lowercase();
-        if let Ok(body) = parsed.get_body() {
+        // A single-part message may itself be an attachment (e.g. one PDF)
+        if let Some(attachment) = extract_attachment(parsed) {
+            parts.attachments.push(attachment);
+        } else if let Some(body) = parts.decode(parsed) {
             if content_type.contains("text/html") {
-                html = Some(body);
+                parts.html = Some(body);
+                preferred = Some(BodySource::HtmlStripped);
             } else {
-                text = body;
+                parts.text = body;
             }
         }
     } else {
-        extract_body_recursive(parsed, &mut text, &mut html);
+        preferred = extract_body_recursive(parsed, &mut parts);
     }
 
-    (text, html)
+    (parts, preferred.unwrap_or_default())
 }
 
+/// Collect the first text and HTML parts and all attachments, returning the
+/// source this subtree favors: the last alternative of a
+/// `multipart/alternative` (RFC 2046), otherwise plain text when present.
 fn extract_body_recursive(
     parsed: &mailparse::ParsedMail,
-    text: &mut String,
-    html: &mut Option<String>,
-) {
+    parts: &mut BodyParts,
+) -> Option<BodySource> {
+    let is_alternative = parsed
+        .ctype
+        .mimetype
+        .eq_ignore_ascii_case("multipart/alternative");
+    let mut preferred = None;
+
     for part in &parsed.subparts {
         let content_type = part.ctype.mimetype.to_lowercase();
 
-        if part.subparts.is_empty() {
-            if let Ok(body) = part.get_body() {
-                if content_type.contains("text/plain") && text.is_empty() {
-                    *text = body;
-                } else if content_type.contains("text/html") && html.is_none() {
-                    *html = Some(body);
+        let source = if !part.subparts.is_empty() {
+            extract_body_recursive(part, parts)
+        } else if content_type == "message/rfc822"
+            && let Ok(inner) = part.get_body_raw()
+        {
+            parts.forwarded.push(inner);
+            None
+        } else if let Some(attachment) = extract_attachment(part) {
+            parts.attachments.push(attachment);
+            None
+        } else {
+            let mut source = None;
+            if let Some(body) = parts.decode(part) {
+                if content_type.contains("text/plain") {
+                    if parts.text.is_empty() {
+                        parts.text = body;
+                    }
+                    source = Some(BodySource::Text);
+                } else if content_type.contains("text/html") {
+                    if parts.html.is_none() {
+                        parts.html = Some(body);
+                    }
+                    source = Some(BodySource::HtmlStripped);
                 }
             }
-        } else {
-            extract_body_recursive(part, text, html);
+            source
+        };
+
+        // Later alternatives are richer; elsewhere plain text wins
+        if let Some(source) = source
+            && (is_alternative || preferred.is_none() || source == BodySource::Text)
+        {
+            preferred = Some(source);
         }
     }
+
+    preferred
 }
 
-fn strip_html(html: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
-    let mut in_script = false;
-    let mut in_style = false;
-    let mut tag_start_idx: usize = 0;
-
-    let lower_chars: Vec<char> = html.to_lowercase().chars().collect();
-    let chars: Vec<char> = html.chars().collect();
-
-    let mut i = 0;
-    while i < chars.len() {
-        if !in_tag && chars[i] == '<' {
-            tag_start_idx = i;
-            // Check for script/style start via char slice
-            let remaining: String = lower_chars[i..].iter().collect();
-            if remaining.starts_with("<script") {
-                in_script = true;
-            } else if remaining.starts_with("<style") {
-                in_style = true;
-            } else if remaining.starts_with("</script") {
-                in_script = false;
-            } else if remaining.starts_with("</style") {
-                in_style = false;
-            }
-            in_tag = true;
-        } else if in_tag && chars[i] == '>' {
-            in_tag = false;
-            // Add newline after block elements
-            let tag_content: String = lower_chars[tag_start_idx + 1..i].iter().collect();
-            if tag_content.starts_with("br")
-                || tag_content.starts_with("/p")
-                || tag_content.starts_with("/div")
-                || tag_content.starts_with("/li")
-                || tag_content.starts_with("/h")
-            {
-                result.push('\n');
-            }
-        } else if !in_tag && !in_script && !in_style {
-            result.push(chars[i]);
-        }
-        i += 1;
+/// Build an attachment for a leaf part that is not body text
+///
+/// Parts marked `attachment`, and any part that is neither `text/plain` nor
+/// `text/html`, count as attachments. Filenames come from the
+/// Content-Disposition `filename` or Content-Type `name` parameter.
+fn extract_attachment(part: &mailparse::ParsedMail) -> Option<Attachment> {
+    let header_value = |name: &str| {
+        part.headers
+            .iter()
+            .find(|h| h.get_key().eq_ignore_ascii_case(name))
+            .map(mailparse::MailHeader::get_value)
+    };
+
+    let (content_type, type_params) = mime::parse_params(
+        &header_value("content-type").unwrap_or_else(|| part.ctype.mimetype.clone()),
+    );
+    let (disposition, disposition_params) =
+        mime::parse_params(&header_value("content-disposition").unwrap_or_default());
+
+    let is_body_text = content_type == "text/plain" || content_type == "text/html";
+    if disposition != "attachment" && is_body_text {
+        return None;
     }
 
-    // Decode HTML entities
-    result = result
-        .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
-        .replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&#39;", "'");
+    let filename = disposition_params
+        .get("filename")
+        .or_else(|| type_params.get("name"))
+        .cloned();
+    let content_id = header_value("content-id").map(|id| {
+        id.trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .to_string()
+    });
 
-    // Clean up whitespace
-    result
-        .lines()
-        .map(str::trim)
-        .filter(|l| !l.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
+    Some(Attachment {
+        filename,
+        content_type,
+        size: part.get_body_raw().map_or(0, |b| b.len()),
+        is_inline: disposition == "inline",
+        content_id,
+    })
 }
 
 fn separate_signature(text: &str) -> (String, Option<String>) {
+    signature_start(text).map_or_else(
+        || (text.to_string(), None),
+        |pos| {
+            (
+                text[..pos].trim().to_string(),
+                Some(text[pos..].trim().to_string()),
+            )
+        },
+    )
+}
+
+/// Offset of the first signature delimiter followed by a non-empty block
+fn signature_start(text: &str) -> Option<usize> {
     // Common signature delimiters
     let delimiters = [
         "--\n",
@@ -376,28 +1230,222 @@ fn separate_signature(text: &str) -> (String, Option<String>) {
         "Regards,",
     ];
 
-    for delimiter in delimiters {
-        if let Some(pos) = text.find(delimiter) {
-            let content = text[..pos].trim().to_string();
-            let signature = text[pos..].trim().to_string();
-            if !signature.is_empty() {
-                return (content, Some(signature));
-            }
+    delimiters.iter().find_map(|delimiter| {
+        text.find(delimiter)
+            .filter(|&pos| !text[pos..].trim().is_empty())
+    })
+}
+
+/// Opening words of common legal and confidentiality disclaimers
+const DISCLAIMER_OPENINGS: &[&str] = &[
+    "this email is confidential",
+    "this e-mail is confidential",
+    "this message is confidential",
+    "this email and any attachments",
+    "this e-mail and any attachments",
+    "this message and any attachments",
+    "this email and any files",
+    "this e-mail and any files",
+    "this email may contain confidential",
+    "this message may contain confidential",
+    "this communication is confidential",
+    "the information contained in this",
+    "the information in this email",
+    "confidentiality notice",
+    "confidentiality note",
+    "disclaimer:",
+    "legal disclaimer",
+    "if you are not the intended recipient",
+    "if you have received this email in error",
+    "if you have received this e-mail in error",
+    "if you have received this message in error",
+    "diese e-mail ist vertraulich",
+    "diese e-mail enthält vertrauliche",
+    "diese nachricht ist vertraulich",
+    "ce message est confidentiel",
+    "ce courriel est confidentiel",
+    "este correo es confidencial",
+    "este mensaje es confidencial",
+];
+
+/// Words of the legal boilerplate following a disclaimer opening
+const DISCLAIMER_TERMS: &[&str] = &[
+    "confidential",
+    "privileged",
+    "intended recipient",
+    "addressee",
+    "in error",
+    "prohibited",
+    "unauthori",
+    "delete",
+    "destroy",
+    "notify",
+    "disclos",
+    "vertraulich",
+    "empfänger",
+    "löschen",
+    "confidentiel",
+    "destinataire",
+    "confidencial",
+    "destinatario",
+];
+
+/// Paragraphs at the end of the text searched for a disclaimer when there
+/// is no signature delimiter before it
+const DISCLAIMER_TRAILING_PARAGRAPHS: usize = 3;
+
+/// Split a trailing legal disclaimer off the text
+///
+/// The disclaimer starts at a line beginning with a known phrase ("This
+/// email is confidential", "Confidentiality notice", ...) and runs to the
+/// end of the text. The line must follow the signature delimiter or sit in
+/// the last paragraphs, and the block must use at least two legal terms
+/// ("intended recipient", "in error", ...), so a sentence like "The
+/// information contained in this report ..." in the message is kept.
+fn separate_disclaimer(text: &str) -> (&str, Option<String>) {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+
+    let paragraph_starts: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|&(i, (_, line))| {
+            !line.trim().is_empty() && (i == 0 || lines[i - 1].1.trim().is_empty())
+        })
+        .map(|(_, &(start, _))| start)
+        .collect();
+    // Start of the first of the last few paragraphs
+    let trailing = paragraph_starts
+        .len()
+        .checked_sub(DISCLAIMER_TRAILING_PARAGRAPHS)
+        .map_or(0, |first| paragraph_starts[first]);
+    let signature = signature_start(text).unwrap_or(text.len());
+
+    for (start, line) in lines {
+        if start < signature && start < trailing {
+            continue;
+        }
+        let opening = line
+            .trim_start_matches(|c: char| c.is_whitespace() || matches!(c, '*' | '_' | '[' | '-'))
+            .to_lowercase();
+        if !DISCLAIMER_OPENINGS
+            .iter()
+            .any(|phrase| opening.starts_with(phrase))
+        {
+            continue;
+        }
+
+        let content = text[..start].trim_end();
+        let disclaimer = text[start..].trim();
+        let lower = disclaimer.to_lowercase();
+        let terms = DISCLAIMER_TERMS
+            .iter()
+            .filter(|term| lower.contains(*term))
+            .count();
+        if !content.is_empty() && terms >= 2 {
+            return (content, Some(disclaimer.to_string()));
         }
     }
 
-    (text.to_string(), None)
+    (text, None)
 }
 
-fn analyze_metadata(
-    from: &EmailAddress,
-    headers: &Headers,
-    subject: &Subject,
-    body: &Body,
-    extracted: &ExtractedEntities,
-) -> EmailMetadata {
+/// Derive spam, urgency, category and sentiment signals from a parsed email
+pub fn analyze_metadata(email: &Email, options: &ParseOptions) -> EmailMetadata {
+    // Decided first: the transactional and account-security checks below
+    // depend on it and `email.metadata` may not be filled in yet
+    let is_automated = detect_automated(email);
+    let mut spam_indicators = detect_spam_indicators(email, options, is_automated);
+    if options.spam.is_trusted(&email.from.domain) {
+        spam_indicators.retain(|i| !TRUSTED_SUPPRESSED_INDICATORS.contains(&i.indicator.as_str()));
+        for indicator in &mut spam_indicators {
+            indicator.weight *= options.spam.trusted_weight_factor;
+        }
+    }
+    let spam_score: f32 = spam_indicators.iter().map(|i| i.weight).sum();
+
+    let mut urgency = detect_urgency(&email.headers, &email.subject, &options.urgency);
+    // Password resets and sign-in alerts need attention
+    if urgency == Urgency::Normal && email.is_account_security_given(is_automated) {
+        urgency = Urgency::High;
+    }
+    let mut category_hints = detect_category_hints(email);
+    category_hints.retain(|hint| hint.confidence >= options.min_category_confidence);
+
+    // Heaviest signals first, ties by name, independent of rule order
+    spam_indicators.sort_by(|a, b| {
+        b.weight
+            .total_cmp(&a.weight)
+            .then_with(|| a.indicator.cmp(&b.indicator))
+    });
+    category_hints.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.category.cmp(&b.category))
+    });
+
+    let precedence = email.headers.precedence;
+    let campaign = email.headers.campaign.is_some();
+    let visible_recipients = email.to.len() + email.cc.len();
+    let is_mailing_list = email.headers.list_unsubscribe.is_some()
+        || campaign
+        || matches!(precedence, Some(Precedence::Bulk | Precedence::List))
+        || options
+            .mailing_list_recipients
+            .is_some_and(|threshold| visible_recipients >= threshold);
+
+    let subject_urgency = detect_subject_urgency(&email.subject, &options.urgency);
+    let sentiment = detect_sentiment(email.body.text_without_disclaimer());
+    let subject_sentiment = detect_sentiment(&email.subject.original);
+
+    EmailMetadata {
+        spam_score: spam_score.min(1.0),
+        spam_indicators,
+        urgency,
+        category_hints,
+        is_automated,
+        is_mailing_list,
+        sentiment,
+        subject_urgency,
+        subject_sentiment,
+    }
+}
+
+/// URL fragments marking login, account and payment pages
+const SENSITIVE_URL_KEYWORDS: &[&str] = &[
+    "login", "log-in", "signin", "sign-in", "logon", "verify", "account", "password", "billing",
+    "payment", "checkout", "wallet", "bank",
+];
+
+/// Sender is a no-reply address, a mailer or campaign tool, or declares
+/// itself as bulk or auto-submitted
+fn detect_automated(email: &Email) -> bool {
+    email.from.is_noreply()
+        || email.headers.mailer.is_some()
+        || email.headers.campaign.is_some()
+        || !email.unfilled_merge_tokens().is_empty()
+        || matches!(
+            email.headers.precedence,
+            Some(Precedence::Bulk | Precedence::Junk)
+        )
+        || email
+            .headers
+            .auto_submitted
+            .as_ref()
+            .is_some_and(AutoSubmitted::is_automated)
+}
+
+fn detect_spam_indicators(
+    email: &Email,
+    options: &ParseOptions,
+    is_automated: bool,
+) -> Vec<SpamIndicator> {
+    let from = &email.from;
     let mut spam_indicators = Vec::new();
-    let mut spam_score: f32 = 0.0;
 
     // Check spam indicators
     if from.is_noreply() {
@@ -405,21 +1453,76 @@ fn analyze_metadata(
             indicator: "noreply_sender".into(),
             weight: 0.1,
         });
-        spam_score += 0.1;
     }
 
-    // Check for tracking URLs
-    let tracking_count = extracted.urls.iter().filter(|u| u.is_tracking).count();
-    if tracking_count > 3 {
+    // Sender domain registered shortly before the message was sent
+    if let Some(registered) = options.domain_info.registered_at(&from.domain)
+        && email.date - registered < options.domain_info.new_domain_age
+    {
         spam_indicators.push(SpamIndicator {
-            indicator: "excessive_tracking".into(),
-            weight: 0.2,
+            indicator: "new_domain".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Several From mailboxes, in one header or across repeated headers
+    if email.from_addresses.len() > 1 {
+        spam_indicators.push(SpamIndicator {
+            indicator: "multiple_from".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Repeated Message-ID, Subject, Date or other single-instance headers;
+    // a repeated From is already `multiple_from`
+    if email.duplicate_headers().iter().any(|&name| name != "from") {
+        spam_indicators.push(SpamIndicator {
+            indicator: "duplicate_header".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Message-ID minted by an unrelated domain
+    if email.message_id.matches_domain(&from.domain) == Some(false) {
+        spam_indicators.push(SpamIndicator {
+            indicator: "messageid_domain_mismatch".into(),
+            weight: 0.1,
         });
-        spam_score += 0.2;
     }
 
+    // No Subject header at all, as opposed to a blank one
+    if !email.subject.was_present {
+        spam_indicators.push(SpamIndicator {
+            indicator: "missing_subject".into(),
+            weight: 0.1,
+        });
+    }
+
+    // Personal message exposing a long visible recipient list
+    if email.possible_recipient_leak() {
+        spam_indicators.push(SpamIndicator {
+            indicator: "possible_recipient_leak".into(),
+            weight: 0.1,
+        });
+    }
+
+    // Sender self-declares the message as bulk or junk
+    match email.headers.precedence {
+        Some(Precedence::Bulk) => spam_indicators.push(SpamIndicator {
+            indicator: "precedence_bulk".into(),
+            weight: 0.1,
+        }),
+        Some(Precedence::Junk) => spam_indicators.push(SpamIndicator {
+            indicator: "precedence_junk".into(),
+            weight: 0.3,
+        }),
+        _ => {}
+    }
+
+    detect_content_indicators(email, options, is_automated, &mut spam_indicators);
+
     // Check subject for spam patterns
-    let subject_lower = subject.original.to_lowercase();
+    let subject_lower = email.subject.original.to_lowercase();
     if subject_lower.contains("urgent")
         || subject_lower.contains("act now")
         || subject_lower.contains("limited time")
@@ -428,22 +1531,120 @@ fn analyze_metadata(
             indicator: "urgency_language".into(),
             weight: 0.15,
         });
-        spam_score += 0.15;
     }
 
-    // Determine urgency
-    let urgency = if subject_lower.contains("urgent")
-        || subject_lower.contains("asap")
-        || subject_lower.contains("emergency")
+    spam_indicators
+}
+
+/// Spam indicators from links, images and placeholders in the body
+fn detect_content_indicators(
+    email: &Email,
+    options: &ParseOptions,
+    is_automated: bool,
+    spam_indicators: &mut Vec<SpamIndicator>,
+) {
+    let extracted = &email.extracted;
+
+    // Check for tracking URLs
+    let tracking_count = extracted.urls.iter().filter(|u| u.is_tracking).count();
+    if let Some(weight) = options.spam.tracking_weight_for(tracking_count) {
+        spam_indicators.push(SpamIndicator {
+            indicator: "excessive_tracking".into(),
+            weight,
+        });
+    }
+
+    // Plain-HTTP links to login or payment pages are a phishing tell
+    if extracted.urls.iter().any(|u| {
+        let url = u.url.to_lowercase();
+        !u.is_secure && SENSITIVE_URL_KEYWORDS.iter().any(|k| url.contains(k))
+    }) {
+        spam_indicators.push(SpamIndicator {
+            indicator: "insecure_sensitive_link".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Transactional-looking mail that never links to its own sender
+    if email.from_domain_link_ratio() == Some(0.0) && email.is_transactional_given(is_automated) {
+        spam_indicators.push(SpamIndicator {
+            indicator: "off_domain_links".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Link text showing one domain while pointing at another
+    if !extracted.misleading_links.is_empty() {
+        spam_indicators.push(SpamIndicator {
+            indicator: "misleading_link".into(),
+            weight: 0.4,
+        });
+    }
+
+    // Placeholders a failed mail merge left in the text
+    if !email.unfilled_merge_tokens().is_empty() {
+        spam_indicators.push(SpamIndicator {
+            indicator: "broken_personalization".into(),
+            weight: 0.3,
+        });
+    }
+
+    // Check for image-only bodies that carry no text to filter on
+    if email.body.is_image_only() {
+        spam_indicators.push(SpamIndicator {
+            indicator: "image_only".into(),
+            weight: 0.4,
+        });
+    }
+
+    // Check for tracking pixels in HTML
+    if !extracted.tracking_pixels.is_empty() {
+        spam_indicators.push(SpamIndicator {
+            indicator: "tracking_pixel".into(),
+            weight: 0.1,
+        });
+    }
+}
+
+fn detect_urgency(headers: &Headers, subject: &Subject, config: &UrgencyConfig) -> Urgency {
+    if detect_subject_urgency(subject, config) == Urgency::High
         || headers.priority == Some(Priority::High)
         || headers.priority == Some(Priority::Highest)
     {
         Urgency::High
     } else {
         Urgency::Normal
-    };
+    }
+}
+
+fn detect_subject_urgency(subject: &Subject, config: &UrgencyConfig) -> Urgency {
+    let subject_lower = subject.original.to_lowercase();
+    if config
+        .keywords
+        .iter()
+        .any(|keyword| contains_word(&subject_lower, keyword))
+    {
+        Urgency::High
+    } else {
+        Urgency::Normal
+    }
+}
+
+/// Whether `needle` occurs in `text` as whole words, not inside a longer
+/// word ("eilig" in "Heiligabend")
+fn contains_word(text: &str, needle: &str) -> bool {
+    !needle.is_empty()
+        && text.match_indices(needle).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + needle.len()..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        })
+}
 
-    // Category hints
+fn detect_category_hints(email: &Email) -> Vec<CategoryHint> {
+    let from = &email.from;
+    let headers = &email.headers;
+    let extracted = &email.extracted;
     let mut category_hints = Vec::new();
 
     if headers.list_unsubscribe.is_some() {
@@ -454,6 +1655,27 @@ fn analyze_metadata(
         });
     }
 
+    if let Some(ref campaign) = headers.campaign {
+        let reason = campaign.platform.as_ref().map_or_else(
+            || "Has campaign tracking headers".to_string(),
+            |platform| format!("Sent through {platform}"),
+        );
+        category_hints.push(CategoryHint {
+            category: "marketing-automation".into(),
+            confidence: 0.85,
+            reason,
+        });
+    }
+
+    let merge_tokens = email.unfilled_merge_tokens();
+    if !merge_tokens.is_empty() {
+        category_hints.push(CategoryHint {
+            category: "bulk".into(),
+            confidence: 0.9,
+            reason: format!("Unfilled merge token {}", merge_tokens[0]),
+        });
+    }
+
     if from.is_noreply() {
         category_hints.push(CategoryHint {
             category: "automated".into(),
@@ -470,12 +1692,26 @@ fn analyze_metadata(
         });
     }
 
-    let is_automated = from.is_noreply() || headers.mailer.is_some();
-    let is_mailing_list = headers.list_unsubscribe.is_some();
+    if let Some(ref event) = email.calendar {
+        let reason = if event.is_cancellation() {
+            "Calendar cancellation"
+        } else {
+            "Calendar invitation"
+        };
+        category_hints.push(CategoryHint {
+            category: "calendar".into(),
+            confidence: 0.95,
+            reason: reason.into(),
+        });
+    }
+
+    category_hints
+}
 
-    // Simple sentiment detection
-    let text_lower = body.best_text().to_lowercase();
-    let sentiment = if text_lower.contains("thank")
+/// Simple keyword-based sentiment detection
+fn detect_sentiment(text: &str) -> Sentiment {
+    let text_lower = text.to_lowercase();
+    if text_lower.contains("thank")
         || text_lower.contains("appreciate")
         || text_lower.contains("great")
         || text_lower.contains("excellent")
@@ -489,15 +1725,5 @@ fn analyze_metadata(
         Sentiment::Negative
     } else {
         Sentiment::Neutral
-    };
-
-    EmailMetadata {
-        spam_score: spam_score.min(1.0),
-        spam_indicators,
-        urgency,
-        category_hints,
-        is_automated,
-        is_mailing_list,
-        sentiment,
     }
 }
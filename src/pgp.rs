@@ -0,0 +1,87 @@
+//! Detection of PGP signing and encryption, both PGP/MIME (RFC 3156) and inline
+//! ASCII armor (RFC 4880) pasted into a plain text body
+
+use crate::types::SecurityInfo;
+use std::borrow::Cow;
+
+const BEGIN_SIGNED: &str = "-----BEGIN PGP SIGNED MESSAGE-----";
+const BEGIN_MESSAGE: &str = "-----BEGIN PGP MESSAGE-----";
+const BEGIN_ARMOR: &str = "-----BEGIN PGP ";
+const END_ARMOR: &str = "-----END PGP ";
+
+/// Signing and encryption of the MIME structure and of the body text
+pub fn security_info(parsed: &mailparse::ParsedMail, text: &str) -> SecurityInfo {
+    let mut info = SecurityInfo::default();
+    mime_security(parsed, &mut info);
+
+    for line in text.lines() {
+        match line.trim_end() {
+            BEGIN_SIGNED => info.signed = true,
+            BEGIN_MESSAGE => info.encrypted = true,
+            _ => continue,
+        }
+        info.inline_pgp = true;
+    }
+
+    info
+}
+
+fn mime_security(parsed: &mailparse::ParsedMail, info: &mut SecurityInfo) {
+    match parsed.ctype.mimetype.to_lowercase().as_str() {
+        "multipart/signed" => info.signed = true,
+        "multipart/encrypted" | "application/pkcs7-mime" | "application/x-pkcs7-mime" => {
+            info.encrypted = true;
+        }
+        _ => {}
+    }
+    for part in &parsed.subparts {
+        mime_security(part, info);
+    }
+}
+
+/// Position of `strip_armor` within the body text
+enum State {
+    /// Unprotected text
+    Text,
+    /// `Hash:` lines after the signed message marker
+    SignedHeaders,
+    /// Signed text up to the signature
+    Cleartext,
+    /// Armored data up to its END line
+    Armor,
+}
+
+/// Body text with inline PGP armor removed
+///
+/// Encrypted messages, signatures and key blocks are dropped; the cleartext
+/// of a signed message is kept without its armor headers and with dash
+/// escaping (`- `) undone. Text without armor is returned unchanged.
+pub fn strip_armor(text: &str) -> Cow<'_, str> {
+    if !text.contains(BEGIN_ARMOR) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut state = State::Text;
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let marker = line.trim_end();
+        state = match state {
+            State::Text | State::Cleartext if marker == BEGIN_SIGNED => State::SignedHeaders,
+            State::Text | State::Cleartext if marker.starts_with(BEGIN_ARMOR) => State::Armor,
+            State::Text => {
+                kept.push(line);
+                State::Text
+            }
+            State::SignedHeaders if marker.is_empty() => State::Cleartext,
+            State::SignedHeaders => State::SignedHeaders,
+            State::Cleartext => {
+                kept.push(line.strip_prefix("- ").unwrap_or(line));
+                State::Cleartext
+            }
+            State::Armor if marker.starts_with(END_ARMOR) => State::Text,
+            State::Armor => State::Armor,
+        };
+    }
+
+    Cow::Owned(kept.join("\n").trim().to_string())
+}
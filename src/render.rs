@@ -0,0 +1,157 @@
+//! Serialization of a parsed email back to RFC 5322 text
+
+use crate::types::{Email, EmailAddress, MessageId};
+use base64::Engine;
+use std::fmt::Write;
+
+/// Longest UTF-8 chunk per encoded word, keeping each word under 75 chars
+const ENCODED_WORD_BYTES: usize = 45;
+
+/// Write headers and body as a CRLF-delimited message
+pub fn email_to_rfc5322(email: &Email) -> String {
+    let mut out = String::new();
+    let mut header = |name: &str, value: &str| {
+        let _ = write!(out, "{name}: {value}\r\n");
+    };
+
+    header("From", &address_list(&email.from_addresses));
+    if !email.to.is_empty() {
+        header("To", &address_list(&email.to));
+    }
+    if !email.cc.is_empty() {
+        header("Cc", &address_list(&email.cc));
+    }
+    if let Some(ref reply_to) = email.reply_to {
+        header("Reply-To", &address(reply_to));
+    }
+    header("Subject", &encode_header_text(&email.subject.original));
+    let date = email
+        .original_date
+        .unwrap_or_else(|| email.date.fixed_offset());
+    header("Date", &date.to_rfc2822());
+    if !email.message_id.is_synthetic() {
+        header("Message-ID", &email.message_id.to_string());
+    }
+    if !email.thread.in_reply_to_all.is_empty() {
+        let in_reply_to: Vec<String> = email
+            .thread
+            .in_reply_to_all
+            .iter()
+            .map(MessageId::to_string)
+            .collect();
+        header("In-Reply-To", &in_reply_to.join(" "));
+    } else if let Some(ref in_reply_to) = email.thread.in_reply_to {
+        header("In-Reply-To", &in_reply_to.to_string());
+    }
+    if !email.thread.references.is_empty() {
+        let references: Vec<String> = email
+            .thread
+            .references
+            .iter()
+            .map(MessageId::to_string)
+            .collect();
+        header("References", &references.join("\r\n "));
+    }
+    for (name, value) in &email.headers.custom {
+        header(name, &encode_header_text(value));
+    }
+    header("MIME-Version", "1.0");
+
+    let body = &email.body;
+    match body.html {
+        Some(ref html) if !body.text.is_empty() => {
+            let boundary = boundary_for(&[&body.text, html]);
+            header(
+                "Content-Type",
+                &format!("multipart/alternative; boundary=\"{boundary}\""),
+            );
+            out.push_str("\r\n");
+            for (content_type, content) in [("text/plain", &body.text), ("text/html", html)] {
+                let _ = write!(
+                    out,
+                    "--{boundary}\r\n\
+                     Content-Type: {content_type}; charset=utf-8\r\n\
+                     Content-Transfer-Encoding: 8bit\r\n\r\n{}\r\n",
+                    crlf(content)
+                );
+            }
+            let _ = write!(out, "--{boundary}--\r\n");
+        }
+        Some(ref html) => {
+            header("Content-Type", "text/html; charset=utf-8");
+            header("Content-Transfer-Encoding", "8bit");
+            let _ = write!(out, "\r\n{}", crlf(html));
+        }
+        None => {
+            header("Content-Type", "text/plain; charset=utf-8");
+            header("Content-Transfer-Encoding", "8bit");
+            let _ = write!(out, "\r\n{}", crlf(&body.text));
+        }
+    }
+
+    out
+}
+
+fn address_list(addresses: &[EmailAddress]) -> String {
+    addresses.iter().map(address).collect::<Vec<_>>().join(", ")
+}
+
+/// `"Display Name" <local@domain>`, encoding non-ASCII names (RFC 2047)
+fn address(address: &EmailAddress) -> String {
+    match address.name {
+        Some(ref name) if name.full.is_ascii() => {
+            let quoted = name.full.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("\"{quoted}\" <{}>", address.address)
+        }
+        Some(ref name) => format!("{} <{}>", encode_words(&name.full), address.address),
+        None => address.address.clone(),
+    }
+}
+
+/// Header text as-is when plain ASCII, otherwise as encoded words
+fn encode_header_text(text: &str) -> String {
+    if text.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+        text.to_string()
+    } else {
+        encode_words(text)
+    }
+}
+
+/// Base64 UTF-8 encoded words, split on character boundaries and folded
+fn encode_words(text: &str) -> String {
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > ENCODED_WORD_BYTES {
+            words.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    words.push(chunk);
+
+    words
+        .iter()
+        .map(|word| {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(word);
+            format!("=?UTF-8?B?{encoded}?=")
+        })
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+/// Multipart boundary that does not occur in any of the parts
+fn boundary_for(parts: &[&str]) -> String {
+    let mut n = 0;
+    loop {
+        let boundary = format!("=_email-extract-{n}");
+        if !parts.iter().any(|part| part.contains(&boundary)) {
+            return boundary;
+        }
+        n += 1;
+    }
+}
+
+/// Normalize line endings to CRLF
+fn crlf(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\n', "\r\n")
+}
@@ -1,12 +1,17 @@
 //! Core types for parsed emails
 
-use crate::extracted::ExtractedEntities;
-use chrono::{DateTime, Utc};
+use crate::extracted::{
+    ExtractedEntities, ExtractedUrl, IpAddress, PhoneNumber, SocialHandle, UrlType,
+};
+use crate::options::{DEFAULT_CLOSING_PHRASES, ExtractionOptions, ParseOptions};
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 /// A fully parsed email with extracted entities and metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Email {
     /// Unique message ID from headers
     pub message_id: MessageId,
@@ -14,9 +19,12 @@ pub struct Email {
     /// IMAP UID
     pub uid: u32,
 
-    /// Sender address
+    /// Sender address (first From mailbox)
     pub from: EmailAddress,
 
+    /// Every From mailbox, including those in repeated From headers
+    pub from_addresses: Vec<EmailAddress>,
+
     /// Primary recipients
     pub to: Vec<EmailAddress>,
 
@@ -38,43 +46,970 @@ pub struct Email {
     /// Date sent/received
     pub date: DateTime<Utc>,
 
+    /// Date header as declared by the sender, keeping its UTC offset
+    pub original_date: Option<DateTime<FixedOffset>>,
+
+    /// Server delivery time (IMAP INTERNALDATE) from
+    /// `ParseOptions::internal_date`
+    pub received_date: Option<DateTime<Utc>>,
+
     /// Email headers
     pub headers: Headers,
 
+    /// Entities extracted from attachment text supplied by the caller
+    /// (e.g. PDF or OCR output), keyed by attachment filename
+    pub attachment_entities: Vec<(String, ExtractedEntities)>,
+
     /// Thread information
     pub thread: ThreadInfo,
 
     /// Extracted entities from the email content
     pub extracted: ExtractedEntities,
 
+    /// Messages attached as `message/rfc822` (forwarded as attachment)
+    pub forwarded: Vec<Self>,
+
+    /// Original headers quoted in the body of an inline forward
+    pub forwarded_headers: Option<ForwardedHeaders>,
+
+    /// Meeting invitation from a `text/calendar` part
+    pub calendar: Option<CalendarEvent>,
+
+    /// Contacts from `text/vcard` parts and `.vcf` attachments
+    pub vcards: Vec<VCard>,
+
+    /// PGP or S/MIME signing and encryption
+    pub security: SecurityInfo,
+
+    /// Per-recipient reports from `message/delivery-status` parts of a
+    /// bounce or delivery notification
+    pub delivery_status: Vec<DeliveryStatus>,
+
+    /// Problems that did not stop parsing, such as unsupported charsets
+    pub warnings: Vec<String>,
+
+    /// Structural problems found in the MIME tree
+    pub structure: StructureHealth,
+
     /// Email metadata and analysis
     pub metadata: EmailMetadata,
 }
 
+/// Number of visible recipients above which a To address counts as bulk
+const BULK_RECIPIENT_THRESHOLD: usize = 10;
+
+/// Subject phrases typical of receipts, confirmations and notifications
+const TRANSACTIONAL_SUBJECTS: &[&str] = &[
+    "confirmation",
+    "confirm your",
+    "receipt",
+    "your order",
+    "order #",
+    "invoice",
+    "password reset",
+    "reset your password",
+    "verification code",
+    "verify your",
+    "has shipped",
+    "shipping update",
+    "payment received",
+    "security alert",
+];
+
+/// Phrases of password resets, sign-in alerts and verification requests
+const ACCOUNT_SECURITY_PHRASES: &[&str] = &[
+    "reset your password",
+    "password reset",
+    "password was changed",
+    "password has been changed",
+    "verify your account",
+    "verify your email",
+    "unusual sign-in",
+    "unusual sign in",
+    "unusual activity",
+    "suspicious activity",
+    "new sign-in",
+    "new login",
+    "login attempt",
+    "security alert",
+    "two-factor",
+    "2-step verification",
+    "two-step verification",
+    "verification code",
+    "one-time code",
+];
+
+/// Body phrases that reference an order or transaction identifier
+const TRANSACTIONAL_BODY: &[&str] = &[
+    "order number",
+    "order #",
+    "order id",
+    "transaction id",
+    "invoice number",
+    "tracking number",
+    "confirmation number",
+];
+
+/// Phrases of cold sales outreach and their calls to action
+const SALES_PHRASES: &[&str] = &[
+    "book a demo",
+    "schedule a demo",
+    "free trial",
+    "quick call",
+    "15 minutes",
+    "our solution",
+    "our platform",
+    "would you be interested",
+    "special offer",
+    "limited time offer",
+];
+
+/// Phrases of a customer complaint
+const COMPLAINT_PHRASES: &[&str] = &[
+    "complaint",
+    "unacceptable",
+    "still waiting",
+    "not working",
+    "doesn't work",
+    "refund",
+    "disappointed",
+    "frustrated",
+    "terrible",
+];
+
+/// Phrases asking the recipient to do or answer something
+const REQUEST_PHRASES: &[&str] = &[
+    "could you",
+    "can you",
+    "would you",
+    "please send",
+    "please confirm",
+    "please review",
+    "please advise",
+    "let me know",
+    "i need",
+    "we need",
+    "request",
+];
+
+/// Headers that may appear at most once (RFC 5322 section 3.6)
+const SINGLE_INSTANCE_HEADERS: &[&str] = &[
+    "date",
+    "from",
+    "sender",
+    "reply-to",
+    "to",
+    "cc",
+    "bcc",
+    "message-id",
+    "in-reply-to",
+    "references",
+    "subject",
+];
+
+/// Order and shipment status phrases, latest or exceptional stages first
+const ORDER_STATUS_PHRASES: &[(&str, OrderStatus)] = &[
+    ("delivery attempt", OrderStatus::DeliveryFailed),
+    ("could not be delivered", OrderStatus::DeliveryFailed),
+    ("unable to deliver", OrderStatus::DeliveryFailed),
+    ("order cancelled", OrderStatus::Cancelled),
+    ("order canceled", OrderStatus::Cancelled),
+    ("has been cancelled", OrderStatus::Cancelled),
+    ("has been canceled", OrderStatus::Cancelled),
+    ("refund issued", OrderStatus::Refunded),
+    ("refund processed", OrderStatus::Refunded),
+    ("has been refunded", OrderStatus::Refunded),
+    ("return received", OrderStatus::Returned),
+    ("received your return", OrderStatus::Returned),
+    ("has been delivered", OrderStatus::Delivered),
+    ("was delivered", OrderStatus::Delivered),
+    ("order delivered", OrderStatus::Delivered),
+    ("package delivered", OrderStatus::Delivered),
+    ("delivered:", OrderStatus::Delivered),
+    ("out for delivery", OrderStatus::OutForDelivery),
+    ("has shipped", OrderStatus::Shipped),
+    ("has been shipped", OrderStatus::Shipped),
+    ("order shipped", OrderStatus::Shipped),
+    ("shipped:", OrderStatus::Shipped),
+    ("has been dispatched", OrderStatus::Shipped),
+    ("is on its way", OrderStatus::Shipped),
+    ("payment received", OrderStatus::PaymentReceived),
+    ("payment confirmed", OrderStatus::PaymentReceived),
+    ("received your payment", OrderStatus::PaymentReceived),
+    ("preparing your order", OrderStatus::Processing),
+    ("processing your order", OrderStatus::Processing),
+    ("order confirmed", OrderStatus::Confirmed),
+    ("order confirmation", OrderStatus::Confirmed),
+    ("thank you for your order", OrderStatus::Confirmed),
+    ("thanks for your order", OrderStatus::Confirmed),
+];
+
+/// Mail-merge placeholders left unfilled: `{{first_name}}`, `%RECIPIENT%`
+/// and Mailchimp's `*|FNAME|*`
+///
+/// `%...%` names need three or more characters so percent-encoded bytes
+/// (`%E2%80%99`) do not match.
+static MERGE_TOKEN_REGEX: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"\{\{\s*[A-Za-z_][\w.\s]{0,40}\}\}|%[A-Za-z][A-Za-z0-9_]{2,40}%|\*\|[A-Za-z0-9_:]{1,40}\|\*")
+        .unwrap()
+});
+
+/// Public suffixes two labels deep, under which registrations take three
+/// labels (`shop.co.uk`)
+const SECOND_LEVEL_SUFFIXES: &[&str] = &[
+    "ac.uk", "co.uk", "gov.uk", "ltd.uk", "me.uk", "net.uk", "org.uk", "plc.uk", "sch.uk",
+    "asn.au", "com.au", "edu.au", "gov.au", "id.au", "net.au", "org.au", "ac.jp", "co.jp", "ne.jp",
+    "or.jp", "ac.nz", "co.nz", "geek.nz", "govt.nz", "net.nz", "org.nz", "ac.za", "co.za",
+    "gov.za", "net.za", "org.za", "co.in", "firm.in", "gen.in", "ind.in", "net.in", "org.in",
+    "com.br", "net.br", "org.br", "com.cn", "net.cn", "org.cn", "com.hk", "org.hk", "com.sg",
+    "edu.sg", "gov.sg", "com.tw", "org.tw", "com.mx", "org.mx", "com.ar", "com.tr", "co.kr",
+    "or.kr", "co.il", "org.il", "co.id", "or.id", "com.my", "com.ph", "com.vn", "com.ua", "com.pl",
+    "net.pl", "org.pl", "co.at", "or.at", "com.es", "com.pt", "com.gr", "com.ru", "com.sa",
+    "com.eg", "com.ng", "co.ke", "com.co", "com.pe", "com.ve", "com.uy",
+];
+
+/// Registrable part of a host name: the last two labels, or three under a
+/// known second-level suffix (`shop.co.uk`)
+fn root_domain(host: &str) -> String {
+    let host = host.rsplit('@').next().unwrap_or_default();
+    let host = host
+        .split([':', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix = labels[labels.len().saturating_sub(2)..].join(".");
+    let keep = if SECOND_LEVEL_SUFFIXES.contains(&suffix.as_str()) {
+        3
+    } else {
+        2
+    };
+    labels[labels.len().saturating_sub(keep)..].join(".")
+}
+
+impl Email {
+    /// Total number of To, CC and BCC recipients
+    #[must_use]
+    pub const fn recipient_count(&self) -> usize {
+        self.to.len() + self.cc.len() + self.bcc.len()
+    }
+
+    /// Determine how the given address was addressed by this email
+    ///
+    /// A To recipient among more than ten visible recipients is treated as
+    /// bulk distribution rather than direct addressing.
+    #[must_use]
+    pub fn recipient_role(&self, address: &str) -> RecipientRole {
+        let matches = |a: &EmailAddress| a.address.eq_ignore_ascii_case(address.trim());
+
+        if self.to.iter().any(matches) {
+            if self.to.len() + self.cc.len() > BULK_RECIPIENT_THRESHOLD {
+                RecipientRole::BulkTo
+            } else {
+                RecipientRole::DirectTo
+            }
+        } else if self.cc.iter().any(matches) {
+            RecipientRole::Cc
+        } else if self.bcc.iter().any(matches) {
+            RecipientRole::Bcc
+        } else {
+            RecipientRole::NotAddressed
+        }
+    }
+
+    /// Count To and Cc recipients by kind of domain, for B2B vs B2C signals
+    ///
+    /// Each distinct address counts once. Freemail addresses are counted as
+    /// such even when the sender uses the same provider; other addresses on
+    /// the sender's domain count as same-domain, the rest as corporate.
+    #[must_use]
+    pub fn recipient_domain_profile(&self) -> DomainProfile {
+        let sender_domain = self.from.domain.trim().to_lowercase();
+        let mut seen = std::collections::HashSet::new();
+        let mut profile = DomainProfile::default();
+
+        for recipient in self.to.iter().chain(&self.cc) {
+            if !seen.insert(recipient.address.to_lowercase()) {
+                continue;
+            }
+            if recipient.is_freemail() {
+                profile.freemail += 1;
+            } else if recipient.domain.trim().eq_ignore_ascii_case(&sender_domain) {
+                profile.same_domain += 1;
+            } else {
+                profile.corporate += 1;
+            }
+        }
+
+        profile
+    }
+
+    /// Heuristic for a personal message sent to a long visible recipient
+    /// list, typically a Bcc mistake exposing everyone's address
+    ///
+    /// Requires more than ten To/Cc recipients, a salutation naming one
+    /// person, and no sign of bulk mail (list or campaign headers, a
+    /// Precedence header or a noreply sender).
+    #[must_use]
+    pub fn possible_recipient_leak(&self) -> bool {
+        let headers = &self.headers;
+        let bulk = headers.list_unsubscribe.is_some()
+            || headers.precedence.is_some()
+            || headers.campaign.is_some()
+            || self.from.is_noreply();
+
+        self.to.len() + self.cc.len() > BULK_RECIPIENT_THRESHOLD
+            && !bulk
+            && self
+                .body
+                .salutation()
+                .is_some_and(|s| s.is_personalized && s.name.is_some())
+    }
+
+    /// Check whether this is an automatic response such as an out-of-office
+    /// notice
+    ///
+    /// Uses `Auto-Submitted: auto-replied`, falling back to the non-standard
+    /// `X-Autoreply` and `X-Autorespond` headers.
+    #[must_use]
+    pub fn is_auto_reply(&self) -> bool {
+        self.headers.auto_submitted == Some(AutoSubmitted::AutoReplied)
+            || !self.headers.get_all("x-autoreply").is_empty()
+            || !self.headers.get_all("x-autorespond").is_empty()
+    }
+
+    /// Unfilled mail-merge tokens in the subject and body, such as
+    /// `{{first_name}}`, `%RECIPIENT%` or `*|FNAME|*`
+    ///
+    /// Left behind when a bulk send's personalization fails; each distinct
+    /// token is listed once, in order of appearance.
+    #[must_use]
+    pub fn unfilled_merge_tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = Vec::new();
+        for text in [self.subject.original.as_str(), self.body.best_text()] {
+            for token in MERGE_TOKEN_REGEX.find_iter(text) {
+                if !tokens.iter().any(|t| t == token.as_str()) {
+                    tokens.push(token.as_str().to_string());
+                }
+            }
+        }
+        tokens
+    }
+
+    /// Single-instance headers (Message-ID, From, Subject, Date, ...) that
+    /// appear more than once, lowercased, in RFC 5322 order
+    ///
+    /// The parser uses the first occurrence; repeats are a header injection
+    /// or spoofing tell.
+    #[must_use]
+    pub fn duplicate_headers(&self) -> Vec<&'static str> {
+        SINGLE_INSTANCE_HEADERS
+            .iter()
+            .copied()
+            .filter(|name| self.headers.get_all(name).len() > 1)
+            .collect()
+    }
+
+    /// Share of links pointing at the sender's root domain
+    ///
+    /// Compares registrable domains, so `mail.acme.com` and
+    /// `www.acme.com` match. Tracking redirects are left out since they
+    /// usually go through the sending platform's domain. `None` when the
+    /// sender has no domain or there are no other links.
+    #[must_use]
+    pub fn from_domain_link_ratio(&self) -> Option<f32> {
+        if self.from.domain.is_empty() {
+            return None;
+        }
+        let sender = root_domain(&self.from.domain);
+        let links: Vec<&ExtractedUrl> = self
+            .extracted
+            .urls
+            .iter()
+            .filter(|url| !url.is_tracking)
+            .collect();
+        if links.is_empty() {
+            return None;
+        }
+
+        let matching = links
+            .iter()
+            .filter(|url| root_domain(&url.domain) == sender)
+            .count();
+        let as_f32 = |n: usize| f32::from(u16::try_from(n).unwrap_or(u16::MAX));
+        Some(as_f32(matching) / as_f32(links.len()))
+    }
+
+    /// Check whether any non-tracking link points at the sender's root
+    /// domain
+    #[must_use]
+    pub fn from_domain_in_links(&self) -> bool {
+        self.from_domain_link_ratio()
+            .is_some_and(|ratio| ratio > 0.0)
+    }
+
+    /// Links to surveys, feedback forms and one-click rating pages
+    #[must_use]
+    pub fn survey_links(&self) -> Vec<&ExtractedUrl> {
+        self.extracted
+            .urls
+            .iter()
+            .filter(|url| url.url_type == UrlType::Survey)
+            .collect()
+    }
+
+    /// UTC offset declared in the Date header
+    #[must_use]
+    pub fn date_offset(&self) -> Option<FixedOffset> {
+        self.original_date.map(|dt| *dt.offset())
+    }
+
+    /// Time between the sender's Date header and server delivery
+    ///
+    /// Negative when the Date header lies in the future of the delivery
+    /// time. `None` unless both dates are known.
+    #[must_use]
+    pub fn delivery_delay(&self) -> Option<TimeDelta> {
+        Some(self.received_date? - self.original_date?.with_timezone(&Utc))
+    }
+
+    /// Check whether the Message-ID domain relates to the From domain
+    ///
+    /// Returns `None` when the Message-ID is synthetic or has no domain.
+    #[must_use]
+    pub fn message_id_domain_matches(&self) -> Option<bool> {
+        self.message_id.matches_domain(&self.from.domain)
+    }
+
+    /// Values of every occurrence of a header, in original order
+    #[must_use]
+    pub fn get_header_all(&self, name: &str) -> Vec<&str> {
+        self.headers.get_all(name)
+    }
+
+    /// List the fields that differ between two parsed emails
+    ///
+    /// Compares the serialized form field by field, so paths follow the
+    /// serde names (e.g. `body.word_count`, `to[1].address`). Objects and
+    /// arrays are compared element-wise; a missing element has a `None`
+    /// side.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<FieldDiff> {
+        let left = serde_json::to_value(self).unwrap_or_default();
+        let right = serde_json::to_value(other).unwrap_or_default();
+        let mut diffs = Vec::new();
+        diff_values(String::new(), Some(&left), Some(&right), &mut diffs);
+        diffs
+    }
+
+    /// IP addresses in the Received chain, most recent hop first
+    ///
+    /// The last public address is usually the one that handed the message
+    /// to the first trusted relay.
+    #[must_use]
+    pub fn received_ips(&self) -> Vec<IpAddress> {
+        let mut ips: Vec<IpAddress> = Vec::new();
+        for header in self.headers.get_all("received") {
+            for ip in IpAddress::find_all(header) {
+                if !ips.contains(&ip) {
+                    ips.push(ip);
+                }
+            }
+        }
+        ips
+    }
+
+    /// Normalized key identifying the logical sender, for reputation and
+    /// rate limiting
+    ///
+    /// The canonical From address; when From has no domain, the canonical
+    /// Return-Path (envelope sender) is used instead if present.
+    #[must_use]
+    pub fn sender_key(&self) -> String {
+        if self.from.domain.trim().is_empty()
+            && let Some(envelope) = self
+                .headers
+                .get_all("return-path")
+                .first()
+                .and_then(|path| EmailAddress::parse(path))
+                .filter(|address| !address.domain.trim().is_empty())
+        {
+            return envelope.canonical();
+        }
+        self.from.canonical()
+    }
+
+    /// Logical correspondent for thread and contact grouping
+    ///
+    /// Precedence: Reply-To when From is a noreply or automated address
+    /// (`EmailAddress::is_noreply()`) and a Reply-To header is present,
+    /// otherwise From. A Reply-To next to a personal From is ignored, as it
+    /// usually only redirects answers to a list or shared inbox.
+    #[must_use]
+    pub fn effective_sender(&self) -> &EmailAddress {
+        match self.reply_to {
+            Some(ref reply_to) if self.from.is_noreply() => reply_to,
+            _ => &self.from,
+        }
+    }
+
+    /// Serialize back to an RFC 5322 message with CRLF line endings
+    ///
+    /// Writes From, To, Cc, Reply-To, Subject, Date, Message-ID (unless
+    /// synthetic), In-Reply-To, References and the custom `X-*` headers,
+    /// followed by the text and HTML body as UTF-8 (`multipart/alternative`
+    /// when both exist). Bcc, other headers and attachments are not written,
+    /// so the output is not byte-identical to the original but parses back
+    /// to the same key fields.
+    #[must_use]
+    pub fn to_rfc5322(&self) -> String {
+        crate::render::email_to_rfc5322(self)
+    }
+
+    /// Record entities extracted from an attachment's text
+    ///
+    /// The entities are stored under the filename in `attachment_entities`
+    /// and merged into `extracted` (see [`ExtractedEntities::merge`]).
+    /// Call [`Email::recompute_metadata`] afterwards to update the metadata.
+    pub fn add_attachment_entities(
+        &mut self,
+        filename: impl Into<String>,
+        entities: ExtractedEntities,
+    ) {
+        self.extracted.merge(&entities);
+        self.attachment_entities.push((filename.into(), entities));
+    }
+
+    /// Check whether this is a reply written by a person rather than an
+    /// automated update posted to the same thread
+    ///
+    /// Requires reply headers or a reply subject, a sender that is not a
+    /// noreply address, no `Auto-Submitted` (other than `no`), Precedence,
+    /// `List-*` or campaign headers, and new text in `Body::reply_text()`.
+    #[must_use]
+    pub fn is_human_reply(&self) -> bool {
+        let auto_submitted = self
+            .headers
+            .auto_submitted
+            .as_ref()
+            .is_some_and(AutoSubmitted::is_automated);
+        let bulk = self.headers.precedence.is_some()
+            || self.headers.campaign.is_some()
+            || self.headers.list_unsubscribe.is_some()
+            || self
+                .headers
+                .all
+                .iter()
+                .any(|(key, _)| key.to_ascii_lowercase().starts_with("list-"));
+
+        self.thread.is_reply
+            && !self.from.is_noreply()
+            && !auto_submitted
+            && !bulk
+            && !self.body.reply_text().is_empty()
+    }
+
+    /// Re-derive `metadata` from the current fields
+    ///
+    /// For pipelines that enrich `extracted` (or other fields) after parsing
+    /// and want spam indicators and category hints to reflect the changes.
+    pub fn recompute_metadata(&mut self) {
+        self.recompute_metadata_with_options(&ParseOptions::default());
+    }
+
+    /// Re-derive `metadata` using custom options (e.g. urgency keywords)
+    pub fn recompute_metadata_with_options(&mut self, options: &ParseOptions) {
+        self.metadata = crate::parser::analyze_metadata(self, options);
+    }
+
+    /// Whether the sender asked to be notified when the message is read
+    #[must_use]
+    pub const fn requests_read_receipt(&self) -> bool {
+        self.headers.receipt_to.is_some()
+    }
+
+    /// Address the read receipt should be sent to, if one was requested
+    #[must_use]
+    pub const fn read_receipt_address(&self) -> Option<&EmailAddress> {
+        self.headers.receipt_to.as_ref()
+    }
+
+    /// Structural problems found in the MIME tree while parsing
+    #[must_use]
+    pub const fn structure_health(&self) -> &StructureHealth {
+        &self.structure
+    }
+
+    /// Heuristic for receipts, confirmations and notifications
+    ///
+    /// Mail carrying `List-*` headers is treated as bulk marketing. Otherwise
+    /// a transactional subject is enough, or an automated sender together
+    /// with an order reference or monetary amount.
+    #[must_use]
+    pub fn is_transactional(&self) -> bool {
+        self.is_transactional_given(self.metadata.is_automated)
+    }
+
+    /// `is_transactional()` with the automated-sender flag supplied by the
+    /// caller, for use while `metadata` is still being derived
+    pub(crate) fn is_transactional_given(&self, is_automated: bool) -> bool {
+        let has_list_headers = self.headers.list_unsubscribe.is_some()
+            || self
+                .headers
+                .all
+                .iter()
+                .any(|(key, _)| key.to_ascii_lowercase().starts_with("list-"));
+        if has_list_headers {
+            return false;
+        }
+
+        let subject = self.subject.original.to_lowercase();
+        if TRANSACTIONAL_SUBJECTS.iter().any(|p| subject.contains(p)) {
+            return true;
+        }
+
+        let automated = self.from.is_noreply() || is_automated;
+        let text = self.body.best_text().to_lowercase();
+        let has_reference = !self.extracted.amounts.is_empty()
+            || !self.extracted.order_numbers.is_empty()
+            || TRANSACTIONAL_BODY.iter().any(|p| text.contains(p));
+
+        automated && has_reference
+    }
+
+    /// Coarse intent of the message for automation routing
+    ///
+    /// The first matching rule wins:
+    ///
+    /// 1. `Confirmation` when `is_transactional()` holds
+    /// 2. `SalesOutreach` for newsletter or marketing-automation category
+    ///    hints, or sales phrases such as "book a demo" in the subject or body
+    /// 3. `Notification` for other automated mail (noreply sender, bulk
+    ///    headers or a calendar invitation)
+    /// 4. `Complaint` for negative sentiment or complaint phrases
+    /// 5. `Request` for a question mark, request phrases such as "could you"
+    ///    or high urgency
+    /// 6. `Fyi` otherwise
+    #[must_use]
+    pub fn intent(&self) -> Intent {
+        if self.is_transactional() {
+            return Intent::Confirmation;
+        }
+
+        let has_hint = |category: &str| {
+            self.metadata
+                .category_hints
+                .iter()
+                .any(|hint| hint.category == category)
+        };
+        let subject = self.subject.original.to_lowercase();
+        let text = self.body.best_text().to_lowercase();
+        let mentions = |phrases: &[&str]| {
+            phrases
+                .iter()
+                .any(|p| subject.contains(p) || text.contains(p))
+        };
+
+        if has_hint("newsletter") || has_hint("marketing-automation") || mentions(SALES_PHRASES) {
+            Intent::SalesOutreach
+        } else if self.metadata.is_automated || self.from.is_noreply() || self.calendar.is_some() {
+            Intent::Notification
+        } else if self.metadata.sentiment == Sentiment::Negative || mentions(COMPLAINT_PHRASES) {
+            Intent::Complaint
+        } else if subject.contains('?')
+            || text.contains('?')
+            || mentions(REQUEST_PHRASES)
+            || matches!(self.metadata.urgency, Urgency::Critical | Urgency::High)
+        {
+            Intent::Request
+        } else {
+            Intent::Fyi
+        }
+    }
+
+    /// Detected language (ISO 639-1 code) of the signature block and
+    /// disclaimer, which often differ from the body in corporate mail
+    #[must_use]
+    pub fn language_of_signature(&self) -> Option<String> {
+        let signature = match (&self.body.signature, &self.body.disclaimer) {
+            (Some(signature), Some(disclaimer)) => format!("{signature}\n{disclaimer}"),
+            (Some(text), None) | (None, Some(text)) => text.clone(),
+            (None, None) => return None,
+        };
+        crate::language::detect(&signature)
+            .into_iter()
+            .next()
+            .map(|(code, _)| code)
+    }
+
+    /// Check whether this is a password reset, sign-in alert, verification
+    /// or other account security message
+    ///
+    /// A security phrase in the subject is enough; one in the body also
+    /// needs a transactional or automated sender (`is_transactional()`, a
+    /// noreply From or `is_automated`), since people discuss passwords too.
+    #[must_use]
+    pub fn is_account_security(&self) -> bool {
+        self.is_account_security_given(self.metadata.is_automated)
+    }
+
+    /// `is_account_security()` with the automated-sender flag supplied by
+    /// the caller, for use while `metadata` is still being derived
+    pub(crate) fn is_account_security_given(&self, is_automated: bool) -> bool {
+        let mentions = |text: &str| {
+            let text = text.to_lowercase();
+            ACCOUNT_SECURITY_PHRASES.iter().any(|p| text.contains(p))
+        };
+        if mentions(&self.subject.original) {
+            return true;
+        }
+        let automated =
+            self.from.is_noreply() || is_automated || self.is_transactional_given(is_automated);
+        automated && mentions(self.body.best_text())
+    }
+
+    /// Order or shipment status from well-known commerce and carrier
+    /// phrases ("Order confirmed", "Out for delivery", "Delivered", ...)
+    ///
+    /// The subject is checked before the body. Within each, the latest or
+    /// exceptional stage wins, so a delivery notice that repeats "thank you
+    /// for your order" is still `Delivered`.
+    #[must_use]
+    pub fn order_status(&self) -> Option<OrderStatus> {
+        let find = |text: &str| {
+            let text = text.to_lowercase();
+            ORDER_STATUS_PHRASES
+                .iter()
+                .find(|(phrase, _)| text.contains(phrase))
+                .map(|&(_, status)| status)
+        };
+        find(&self.subject.original).or_else(|| find(self.body.best_text()))
+    }
+
+    /// Stable hex SHA-256 of the message content, for deduplication
+    ///
+    /// Covers the Message-ID (empty when synthetic), the lowercased From
+    /// address, the decoded subject and the best body text with whitespace
+    /// runs collapsed. The UID, dates, other headers and extracted entities
+    /// are left out, so the same message fetched from different folders or
+    /// servers hashes identically.
+    #[cfg(feature = "hash")]
+    #[must_use]
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        use std::fmt::Write;
+
+        let message_id = if self.message_id.is_synthetic() {
+            ""
+        } else {
+            self.message_id.as_str()
+        };
+        let from = self.from.address.to_lowercase();
+        let body = self
+            .body
+            .best_text()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut hasher = Sha256::new();
+        for field in [message_id, &from, self.subject.original.trim(), &body] {
+            hasher.update((field.len() as u64).to_be_bytes());
+            hasher.update(field.as_bytes());
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .fold(String::with_capacity(64), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+}
+
+/// Recursively collect differences between two serialized values
+fn diff_values(
+    path: String,
+    left: Option<&serde_json::Value>,
+    right: Option<&serde_json::Value>,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    use serde_json::Value;
+
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    match (left, right) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let keys = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k)));
+            for key in keys {
+                diff_values(child(key), a.get(key), b.get(key), diffs);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_values(format!("{path}[{i}]"), a.get(i), b.get(i), diffs);
+            }
+        }
+        (left, right) if left != right => diffs.push(FieldDiff {
+            path,
+            left: left.cloned(),
+            right: right.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+/// One differing field reported by [`Email::diff`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// Dotted path to the field, with `[n]` for array elements
+    pub path: String,
+
+    /// Value in `self`, `None` if absent
+    pub left: Option<serde_json::Value>,
+
+    /// Value in `other`, `None` if absent
+    pub right: Option<serde_json::Value>,
+}
+
+/// Recipients grouped by kind of domain (`Email::recipient_domain_profile()`)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DomainProfile {
+    /// Consumer mailbox providers such as Gmail or Outlook.com
+    pub freemail: usize,
+
+    /// Business domains other than the sender's
+    pub corporate: usize,
+
+    /// Addresses on the sender's own domain
+    pub same_domain: usize,
+}
+
+impl DomainProfile {
+    /// Total number of recipients counted
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.freemail + self.corporate + self.same_domain
+    }
+
+    /// Check whether external recipients are mostly on business domains
+    ///
+    /// Same-domain recipients are internal and not counted either way.
+    #[must_use]
+    pub const fn is_mostly_corporate(&self) -> bool {
+        self.corporate > self.freemail
+    }
+}
+
+/// How a particular address was addressed by an email
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecipientRole {
+    /// Listed in To alongside few other recipients
+    DirectTo,
+    /// Listed in CC
+    Cc,
+    /// Listed in BCC
+    Bcc,
+    /// Listed in To among many visible recipients
+    BulkTo,
+    /// Not listed in any recipient header
+    NotAddressed,
+}
+
 /// Message ID wrapper type
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct MessageId(pub String);
 
 impl MessageId {
+    /// Create an ID, stripping surrounding whitespace and angle brackets
+    ///
+    /// `<abc@example.com>` and `abc@example.com` produce equal IDs.
     pub fn new(id: impl Into<String>) -> Self {
-        Self(id.into())
+        let id = id.into();
+        Self(
+            id.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .trim()
+                .to_string(),
+        )
     }
 
     /// Generate a synthetic message ID if none provided
     #[must_use]
     pub fn synthetic(uid: u32) -> Self {
-        Self(format!("<synthetic-{uid}@local>"))
+        Self(format!("synthetic-{uid}@local"))
+    }
+
+    /// Check for the `local@domain` shape of RFC 5322 `msg-id`
+    ///
+    /// Both sides must be non-empty, separated by a single `@`, and made of
+    /// printable ASCII without angle brackets.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let Some((local, domain)) = self.0.split_once('@') else {
+            return false;
+        };
+        let valid_part = |part: &str| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_graphic() && !matches!(b, b'<' | b'>' | b'@'))
+        };
+        valid_part(local) && valid_part(domain)
     }
 
     #[must_use]
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Check if this ID was generated by `synthetic()`
+    #[must_use]
+    pub fn is_synthetic(&self) -> bool {
+        self.0.starts_with("synthetic-") && self.0.ends_with("@local")
+    }
+
+    /// Domain part of the ID (after the last `@`)
+    #[must_use]
+    pub fn domain(&self) -> Option<&str> {
+        self.0
+            .rsplit_once('@')
+            .map(|(_, domain)| domain)
+            .filter(|domain| !domain.is_empty())
+    }
+
+    /// Check whether the ID domain relates to the given sender domain
+    ///
+    /// Subdomains in either direction count as related. Returns `None` for
+    /// synthetic IDs and IDs without a domain.
+    #[must_use]
+    pub fn matches_domain(&self, domain: &str) -> Option<bool> {
+        if self.is_synthetic() {
+            return None;
+        }
+        let id_domain = self.domain()?.to_lowercase();
+        let domain = domain.to_lowercase();
+
+        Some(
+            id_domain == domain
+                || id_domain.ends_with(&format!(".{domain}"))
+                || domain.ends_with(&format!(".{id_domain}")),
+        )
+    }
 }
 
 impl fmt::Display for MessageId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "<{}>", self.0)
     }
 }
 
@@ -94,17 +1029,64 @@ pub struct EmailAddress {
     pub local_part: String,
 }
 
+/// Byte positions of the `<` and `>` around the address in a mailbox
+///
+/// Brackets inside quoted strings belong to the display name and are
+/// skipped; of the remaining pairs the last one wins, so a name such as
+/// `<Team> Support` keeps its brackets.
+fn angle_address_span(s: &str) -> Option<(usize, usize)> {
+    let mut in_quotes = false;
+    let mut escaped = false;
+    let mut open = None;
+    let mut span = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '<' if !in_quotes => open = Some(i),
+            '>' if !in_quotes => {
+                if let Some(start) = open.take() {
+                    span = Some((start, i));
+                }
+            }
+            _ => {}
+        }
+    }
+    span
+}
+
+/// Display name with surrounding quotes and quoted-pair escapes removed
+fn unquote_display_name(name: &str) -> String {
+    let Some(inner) = name
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return name.trim_matches('"').to_string();
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            result.extend(chars.next());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
 impl EmailAddress {
     /// Parse an email address from a string
     #[must_use]
     pub fn parse(s: &str) -> Option<Self> {
         let s = s.trim();
 
-        // Try to match "Name <email@domain.com>" format
-        if let Some(start) = s.find('<')
-            && let Some(end) = s.find('>')
-        {
-            let name_part = s[..start].trim().trim_matches('"');
+        // Try to match "Name <email@domain.com>" format, where the address
+        // is the last bracketed pair outside the quoted display name
+        if let Some((start, end)) = angle_address_span(s) {
+            let name_part = unquote_display_name(s[..start].trim());
             let address = s[start + 1..end].trim().to_string();
 
             if let Some((local, domain)) = address.split_once('@') {
@@ -112,7 +1094,7 @@ impl EmailAddress {
                     name: if name_part.is_empty() {
                         None
                     } else {
-                        Some(PersonName::parse(name_part))
+                        Some(PersonName::parse(&name_part))
                     },
                     local_part: local.to_string(),
                     domain: domain.to_string(),
@@ -134,6 +1116,26 @@ impl EmailAddress {
         None
     }
 
+    /// Canonical form of the address for use as a lookup key
+    ///
+    /// Lowercased; for Gmail (and `googlemail.com`) dots and `+tag` suffixes
+    /// in the local part are dropped, since they reach the same mailbox.
+    #[must_use]
+    pub fn canonical(&self) -> String {
+        let mut local = self.local_part.to_lowercase();
+        let mut domain = self.domain.trim().to_lowercase();
+
+        if domain == "gmail.com" || domain == "googlemail.com" {
+            if let Some((base, _)) = local.split_once('+') {
+                local = base.to_string();
+            }
+            local.retain(|c| c != '.');
+            domain = "gmail.com".to_string();
+        }
+
+        format!("{local}@{domain}")
+    }
+
     /// Check if this is likely a noreply/automated address
     #[must_use]
     pub fn is_noreply(&self) -> bool {
@@ -219,7 +1221,7 @@ impl fmt::Display for PersonName {
 }
 
 /// Email subject with analysis
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Subject {
     /// Original subject line
     pub original: String,
@@ -235,6 +1237,9 @@ pub struct Subject {
 
     /// Detected language (ISO 639-1 code)
     pub language: Option<String>,
+
+    /// Whether the message had a Subject header at all
+    pub was_present: bool,
 }
 
 impl Subject {
@@ -284,6 +1289,16 @@ impl Subject {
             reply_depth,
             is_forward,
             language: None, // Could add language detection
+            was_present: true,
+        }
+    }
+
+    /// Empty subject for a message without a Subject header
+    #[must_use]
+    pub fn missing() -> Self {
+        Self {
+            was_present: false,
+            ..Self::parse("")
         }
     }
 }
@@ -294,18 +1309,39 @@ impl fmt::Display for Subject {
     }
 }
 
+/// Smallest inline image part counted by `Body::is_image_only()`
+const IMAGE_ONLY_MIN_BYTES: usize = 10 * 1024;
+
+/// Word count from which a body is no longer considered image-only
+const IMAGE_ONLY_MAX_WORDS: usize = 20;
+
+/// Reading speed used by `Body::reading_time()`
+pub const DEFAULT_WORDS_PER_MINUTE: u32 = 200;
+
 /// Email body content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Body {
     /// Plain text content
     pub text: String,
 
+    /// Decoded `text/plain` part exactly as received, untrimmed and with
+    /// blank lines intact (only with `ParseOptions::preserve_formatting`)
+    pub raw_text: Option<String>,
+
     /// HTML content (if available)
     pub html: Option<String>,
 
-    /// Text extracted from HTML (if HTML-only email)
+    /// Text extracted from HTML (if HTML-only email or HTML is the
+    /// preferred alternative)
     pub text_from_html: Option<String>,
 
+    /// Hidden preview text from the top of an HTML body, kept out of
+    /// `text_from_html`
+    pub preheader: Option<String>,
+
+    /// Part preferred by the MIME structure (last `multipart/alternative`)
+    pub preferred_source: BodySource,
+
     /// Word count of text content
     pub word_count: usize,
 
@@ -315,15 +1351,22 @@ pub struct Body {
     /// Line count
     pub line_count: usize,
 
-    /// Detected language
+    /// Detected language (ISO 639-1 code) of the body text
     pub language: Option<String>,
 
     /// Has attachments indicator from content type
     pub has_attachments: bool,
 
+    /// Attachments and inline non-text parts
+    pub attachments: Vec<Attachment>,
+
     /// Signature block (if detected and separated)
     pub signature: Option<String>,
 
+    /// Trailing legal or confidentiality disclaimer (if detected), kept out
+    /// of `signature` and `content_without_signature`
+    pub disclaimer: Option<String>,
+
     /// Main content without signature
     pub content_without_signature: String,
 }
@@ -338,20 +1381,520 @@ impl Body {
     /// Get the best available text content
     #[must_use]
     pub fn best_text(&self) -> &str {
-        if !self.text.is_empty() {
-            &self.text
-        } else if let Some(ref html_text) = self.text_from_html {
-            html_text
+        match self.primary_source() {
+            BodySource::Text => &self.text,
+            BodySource::HtmlStripped => self.text_from_html.as_deref().unwrap_or(""),
+        }
+    }
+
+    /// `best_text()` up to the legal disclaimer, which entity extraction,
+    /// counts and sentiment skip
+    #[must_use]
+    pub fn text_without_disclaimer(&self) -> &str {
+        let text = self.best_text();
+        self.disclaimer
+            .as_deref()
+            .and_then(|disclaimer| text.rfind(disclaimer))
+            .map_or(text, |pos| text[..pos].trim_end())
+    }
+
+    /// New text of a reply, without the quoted history below it
+    ///
+    /// Skips `>`-quoted lines and stops at an attribution line ("On ...
+    /// wrote:"), an "-----Original Message-----" separator or an Outlook
+    /// style `From:` header block.
+    #[must_use]
+    pub fn reply_text(&self) -> String {
+        let mut lines = Vec::new();
+        for line in self.best_text().lines() {
+            let trimmed = line.trim();
+            let lower = trimmed.to_lowercase();
+            let starts_history = (lower.starts_with("on ") && lower.ends_with("wrote:"))
+                || (lower.starts_with("-----") && lower.contains("original message"))
+                || lower.starts_with("from:");
+            if starts_history {
+                break;
+            }
+            if !trimmed.starts_with('>') {
+                lines.push(line);
+            }
+        }
+        lines.join("\n").trim().to_string()
+    }
+
+    /// Estimated reading time of the new text at
+    /// [`DEFAULT_WORDS_PER_MINUTE`]
+    #[must_use]
+    pub fn reading_time(&self) -> Duration {
+        self.reading_time_at(DEFAULT_WORDS_PER_MINUTE)
+    }
+
+    /// Estimated reading time of the new text at the given speed
+    ///
+    /// Counts the words of `reply_text()`, so quoted history does not
+    /// inflate replies. A speed of 0 is treated as 1 word per minute.
+    #[must_use]
+    pub fn reading_time_at(&self, words_per_minute: u32) -> Duration {
+        let words = self.reply_text().split_whitespace().count();
+        let words = u64::try_from(words).unwrap_or(u64::MAX);
+        Duration::from_millis(words.saturating_mul(60_000) / u64::from(words_per_minute.max(1)))
+    }
+
+    /// Average sentence and word length of the new text (`reply_text()`)
+    #[must_use]
+    pub fn complexity(&self) -> TextComplexity {
+        let text = self.reply_text();
+        let sentences = text
+            .split(['.', '!', '?'])
+            .filter(|sentence| sentence.split_whitespace().next().is_some())
+            .count();
+        let (words, word_chars) = text
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .fold((0, 0), |(words, chars), word| {
+                (words + 1, chars + word.chars().count())
+            });
+
+        let ratio = |total: usize, count: usize| {
+            if count == 0 {
+                return 0.0;
+            }
+            let total = u32::try_from(total).unwrap_or(u32::MAX);
+            let count = u32::try_from(count).unwrap_or(u32::MAX);
+            f64::from(total) / f64::from(count)
+        };
+        TextComplexity {
+            sentences,
+            avg_sentence_words: ratio(words, sentences),
+            avg_word_chars: ratio(word_chars, words),
+        }
+    }
+
+    /// Candidate languages of the body text with their confidence, best first
+    ///
+    /// Confidences are each language's share of recognised function words,
+    /// so a bilingual message reports two candidates of similar weight.
+    /// `language` holds the top candidate. The signature block is left out
+    /// (see `Email::language_of_signature()`).
+    #[must_use]
+    pub fn detected_languages(&self) -> Vec<(String, f64)> {
+        if self.content_without_signature.trim().is_empty() {
+            crate::language::detect(self.best_text())
         } else {
-            ""
+            crate::language::detect(&self.content_without_signature)
+        }
+    }
+
+    /// Writing direction of `best_text()`, for setting `dir` when rendering
+    ///
+    /// `Rtl` or `Ltr` when at least 80% of the letters are from right-to-left
+    /// scripts (Hebrew, Arabic, ...) or from other scripts, `Mixed`
+    /// otherwise. A leading bidi mark (RLM, LRM, ...) takes precedence.
+    #[must_use]
+    pub fn text_direction(&self) -> TextDirection {
+        crate::language::text_direction(self.best_text())
+    }
+
+    /// Check whether the message is essentially one or more images
+    ///
+    /// True when the body shows an image (an inline image part of at least
+    /// 10 KiB, or an HTML `<img>` that is not a tracking pixel) and
+    /// `best_text()` has fewer than 20 words.
+    #[must_use]
+    pub fn is_image_only(&self) -> bool {
+        let inline_image = self.attachments.iter().any(|attachment| {
+            attachment.content_type.starts_with("image/")
+                && (attachment.is_inline || attachment.content_id.is_some())
+                && attachment.size >= IMAGE_ONLY_MIN_BYTES
+        });
+        let html_image = self.html.as_deref().is_some_and(|html| {
+            crate::html::image_count(html) > crate::html::tracking_pixels(html).len()
+        });
+
+        (inline_image || html_image)
+            && self.best_text().split_whitespace().count() < IMAGE_ONLY_MAX_WORDS
+    }
+
+    /// Source that `best_text()` is derived from
+    ///
+    /// Honors the preferred alternative when its content is available and
+    /// otherwise falls back to whichever part is non-empty.
+    #[must_use]
+    pub const fn primary_source(&self) -> BodySource {
+        match self.preferred_source {
+            BodySource::HtmlStripped if self.text_from_html.is_some() => BodySource::HtmlStripped,
+            _ if !self.text.is_empty() => BodySource::Text,
+            _ if self.text_from_html.is_some() => BodySource::HtmlStripped,
+            _ => BodySource::Text,
         }
     }
 }
 
+/// Writing direction returned by `Body::text_direction()`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Left-to-right
+    #[default]
+    Ltr,
+    /// Right-to-left (Hebrew, Arabic, ...)
+    Rtl,
+    /// Both directions without a dominant one
+    Mixed,
+}
+
+/// Readability measures returned by `Body::complexity()`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct TextComplexity {
+    /// Sentences, split on `.`, `!` and `?`
+    pub sentences: usize,
+
+    /// Average words per sentence
+    pub avg_sentence_words: f64,
+
+    /// Average characters per word, ignoring surrounding punctuation
+    pub avg_word_chars: f64,
+}
+
+/// File attached to an email
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attachment {
+    /// Decoded filename (RFC 2231 and RFC 2047 aware)
+    pub filename: Option<String>,
+
+    /// MIME type (lowercase)
+    pub content_type: String,
+
+    /// Decoded size in bytes
+    pub size: usize,
+
+    /// Displayed inline (e.g. embedded image) rather than as a download
+    pub is_inline: bool,
+
+    /// Content-ID used to reference inline parts
+    pub content_id: Option<String>,
+}
+
+/// Greetings recognized at the start of a body, longest first
+const GREETINGS: &[&str] = &[
+    "good morning",
+    "good afternoon",
+    "good evening",
+    "greetings",
+    "hello",
+    "dear",
+    "hiya",
+    "hey",
+    "hi",
+];
+
+/// Addressees that indicate a mail-merge or generic greeting
+const GENERIC_ADDRESSEES: &[&str] = &[
+    "all",
+    "client",
+    "colleagues",
+    "customer",
+    "everybody",
+    "everyone",
+    "folks",
+    "friend",
+    "friends",
+    "guys",
+    "madam",
+    "member",
+    "sir",
+    "sir or madam",
+    "sir/madam",
+    "subscriber",
+    "team",
+    "there",
+    "user",
+    "valued customer",
+    "valued member",
+];
+
+/// Honorifics that may precede an addressed name
+const HONORIFICS: &[&str] = &[
+    "mr", "mr.", "mrs", "mrs.", "ms", "ms.", "miss", "dr", "dr.", "prof", "prof.",
+];
+
+impl Body {
+    /// Parse the greeting line opening the body (e.g. "Hi John,")
+    #[must_use]
+    pub fn salutation(&self) -> Option<Salutation> {
+        let line = self
+            .best_text()
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())?;
+
+        let greeting = GREETINGS.iter().find(|g| {
+            line.get(..g.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(g))
+                && line[g.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(|c| !c.is_alphanumeric())
+        })?;
+        let (greeting, rest) = line.split_at(greeting.len());
+
+        let addressee = rest.split([',', '!', ':', ';']).next().unwrap_or("").trim();
+        let mut words: Vec<&str> = addressee.split_whitespace().collect();
+        let title = words
+            .first()
+            .filter(|w| HONORIFICS.contains(&w.to_lowercase().as_str()))
+            .map(ToString::to_string);
+        if title.is_some() {
+            words.remove(0);
+        }
+
+        let is_generic = addressee.is_empty()
+            || GENERIC_ADDRESSEES.contains(&words.join(" ").to_lowercase().as_str());
+        let is_name = !words.is_empty()
+            && words.len() <= 4
+            && words
+                .iter()
+                .all(|w| w.chars().next().is_some_and(char::is_uppercase));
+
+        let name = (is_name && !is_generic).then(|| PersonName::parse(&words.join(" ")));
+
+        Some(Salutation {
+            greeting: greeting.to_string(),
+            title,
+            is_personalized: name.is_some(),
+            name,
+        })
+    }
+
+    /// Parse the signature into contact fields
+    ///
+    /// Entities are extracted from the signature text only, with the same
+    /// `options` as the body (`ParseOptions::extraction`) so both agree. The
+    /// first plain line after the closing is taken as the name and the next
+    /// as the job title; "Title at Company" and "Title | Company" lines are
+    /// split.
+    #[must_use]
+    pub fn parsed_signature(&self, options: &ExtractionOptions) -> Option<SignatureBlock> {
+        let signature = self.signature.as_deref()?;
+        let extracted = ExtractedEntities::extract_with_options(signature, options);
+
+        // Lines without contact details, after the delimiter and closing
+        let plain: Vec<&str> = signature
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.chars().all(|c| c == '-' || c == '_'))
+            .filter(|l| {
+                let lower = l.to_lowercase();
+                !SIGNATURE_CLOSINGS.iter().any(|c| lower.starts_with(c))
+            })
+            .filter(|l| {
+                let lower = l.to_lowercase();
+                !lower.contains('@')
+                    && !lower.contains("http")
+                    && !lower.contains("www.")
+                    && l.chars().filter(char::is_ascii_digit).count() < 5
+            })
+            .collect();
+
+        let mut lines = plain.iter().copied();
+        let name = lines
+            .next()
+            .filter(|l| {
+                let words: Vec<&str> = l.split_whitespace().collect();
+                words.len() <= 4
+                    && words
+                        .iter()
+                        .all(|w| w.chars().next().is_some_and(char::is_uppercase))
+            })
+            .map(PersonName::parse);
+
+        let mut company = extracted.companies.first().cloned();
+        let mut title = None;
+        if name.is_some()
+            && let Some(line) = lines.next()
+            && company.as_deref() != Some(line)
+        {
+            let split = line
+                .split_once(" | ")
+                .or_else(|| line.split_once(" at "))
+                .or_else(|| line.split_once(", "));
+            match split {
+                Some((role, org)) => {
+                    title = Some(role.trim().to_string());
+                    company = company.or_else(|| Some(org.trim().to_string()));
+                }
+                None => title = Some(line.to_string()),
+            }
+            if company.is_none() {
+                company = lines.next().map(ToString::to_string);
+            }
+        }
+
+        Some(SignatureBlock {
+            name,
+            title,
+            company,
+            phones: extracted.phone_numbers,
+            emails: extracted.emails.into_iter().map(|e| e.address).collect(),
+            urls: extracted.urls,
+            social: extracted.social_handles,
+        })
+    }
+}
+
+impl Body {
+    /// Find the sign-off line (e.g. "Thanks, Alice") using
+    /// [`DEFAULT_CLOSING_PHRASES`]
+    #[must_use]
+    pub fn closing(&self) -> Option<Closing> {
+        self.closing_with_phrases(DEFAULT_CLOSING_PHRASES)
+    }
+
+    /// Find the sign-off line using custom lowercase closing phrases
+    ///
+    /// The last unquoted line starting with a phrase wins, provided the rest
+    /// of the line is empty or a name; otherwise it is taken to be ordinary
+    /// prose ("Thanks for the update"). Without a name on the same line, a
+    /// short capitalized line right below is used. Nothing after the closing
+    /// is treated as signature.
+    #[must_use]
+    pub fn closing_with_phrases<S: AsRef<str>>(&self, phrases: &[S]) -> Option<Closing> {
+        let lines: Vec<&str> = self
+            .best_text()
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('>'))
+            .collect();
+
+        lines.iter().enumerate().rev().find_map(|(i, line)| {
+            let phrase = phrases
+                .iter()
+                .map(AsRef::as_ref)
+                .filter(|p| {
+                    line.get(..p.len())
+                        .is_some_and(|prefix| prefix.to_lowercase() == *p)
+                        && line[p.len()..]
+                            .chars()
+                            .next()
+                            .is_none_or(|c| !c.is_alphanumeric())
+                })
+                .max_by_key(|p| p.len())?;
+            let (phrase, rest) = line.split_at(phrase.len());
+
+            let rest = rest.trim_matches(|c: char| c.is_whitespace() || ",.!;:-".contains(c));
+            let name = if rest.is_empty() {
+                lines
+                    .get(i + 1)
+                    .copied()
+                    .filter(|next| looks_like_name(next))
+            } else if looks_like_name(rest) {
+                Some(rest)
+            } else {
+                return None;
+            };
+
+            Some(Closing {
+                phrase: phrase.to_string(),
+                name: name.map(PersonName::parse),
+            })
+        })
+    }
+}
+
+/// Short run of capitalized words without digits or contact details
+fn looks_like_name(text: &str) -> bool {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (1..=3).contains(&words.len())
+        && words
+            .iter()
+            .all(|w| w.chars().next().is_some_and(char::is_uppercase))
+        && !text.chars().any(|c| c.is_ascii_digit() || c == '@')
+}
+
+/// Sign-off line closing an email body
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Closing {
+    /// Closing phrase as written (e.g. "Best regards", "Merci")
+    pub phrase: String,
+
+    /// Name signed after the phrase, on the same or the following line
+    pub name: Option<PersonName>,
+}
+
+/// Closing phrases that precede the sender's name in a signature
+const SIGNATURE_CLOSINGS: &[&str] = &[
+    "best",
+    "kind regards",
+    "regards",
+    "warm regards",
+    "thanks",
+    "thank you",
+    "cheers",
+    "sincerely",
+    "yours",
+    "cordialement",
+    "mit freundlichen",
+    "viele grüße",
+];
+
+/// Contact fields parsed from a signature block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignatureBlock {
+    /// Sender name
+    pub name: Option<PersonName>,
+
+    /// Job title
+    pub title: Option<String>,
+
+    /// Company name
+    pub company: Option<String>,
+
+    /// Phone numbers in the signature
+    pub phones: Vec<PhoneNumber>,
+
+    /// Email addresses in the signature
+    pub emails: Vec<String>,
+
+    /// Links in the signature
+    pub urls: Vec<ExtractedUrl>,
+
+    /// Social media handles in the signature
+    pub social: Vec<SocialHandle>,
+}
+
+/// Greeting line opening an email body
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Salutation {
+    /// Greeting as written (e.g. "Dear", "Hi")
+    pub greeting: String,
+
+    /// Honorific preceding the name (e.g. "Ms.")
+    pub title: Option<String>,
+
+    /// Addressed person, when a specific name was used
+    pub name: Option<PersonName>,
+
+    /// False for generic greetings like "Dear Customer" or "Hi there"
+    pub is_personalized: bool,
+}
+
+/// Origin of the text used for analysis
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BodySource {
+    /// The `text/plain` part
+    #[default]
+    Text,
+    /// Text stripped from the `text/html` part
+    HtmlStripped,
+}
+
 /// Email headers
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Headers {
-    /// All headers as key-value pairs
+    /// Every header as a (lowercase key, value) pair
+    ///
+    /// Complete and in original order: repeated headers such as `Received`
+    /// or `DKIM-Signature` appear once per occurrence.
     pub all: Vec<(String, String)>,
 
     /// Content-Type
@@ -360,12 +1903,37 @@ pub struct Headers {
     /// X-Mailer or User-Agent
     pub mailer: Option<String>,
 
-    /// X-Priority
+    /// X-Priority, or Importance when X-Priority is absent
     pub priority: Option<Priority>,
 
     /// List-Unsubscribe header (newsletters)
     pub list_unsubscribe: Option<String>,
 
+    /// Precedence header (bulk, list or junk)
+    pub precedence: Option<Precedence>,
+
+    /// Auto-Submitted header (RFC 3834)
+    pub auto_submitted: Option<AutoSubmitted>,
+
+    /// Brand indicator (BIMI) declarations
+    pub bimi: Option<BimiInfo>,
+
+    /// Marketing automation campaign identifiers
+    pub campaign: Option<CampaignInfo>,
+
+    /// Address asking for a receipt: Disposition-Notification-To, or the
+    /// legacy X-Confirm-Reading-To and Return-Receipt-To
+    pub receipt_to: Option<EmailAddress>,
+
+    /// Mailboxes from Delivered-To headers added at local delivery, most
+    /// recent first; unlike To, these name the alias that received the
+    /// message
+    pub delivered_to: Vec<EmailAddress>,
+
+    /// Envelope recipient from X-Original-To (Postfix), before alias
+    /// expansion
+    pub x_original_to: Option<EmailAddress>,
+
     /// Authentication results
     pub authentication: AuthenticationResults,
 
@@ -373,6 +1941,74 @@ pub struct Headers {
     pub custom: Vec<(String, String)>,
 }
 
+impl Headers {
+    /// Values of every occurrence of a header, in original order
+    ///
+    /// The name is matched case-insensitively.
+    #[must_use]
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.all
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// `mailto:` target of List-Unsubscribe as a ready-to-send message
+    ///
+    /// Query parameters are percent-decoded (RFC 6068); extra `to`
+    /// parameters are ignored.
+    #[must_use]
+    pub fn unsubscribe_mailto(&self) -> Option<UnsubscribeMailto> {
+        let value = self.list_unsubscribe.as_deref()?;
+        let target = crate::mime::split_unquoted(value, ',')
+            .into_iter()
+            .map(|entry| {
+                entry
+                    .trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+            .find(|entry| {
+                entry
+                    .get(..7)
+                    .is_some_and(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+            })?;
+
+        let decode =
+            |text: &str| String::from_utf8_lossy(&crate::mime::percent_decode(text)).into_owned();
+        let target = &target[7..];
+        let (address, query) = target.split_once('?').unwrap_or((target, ""));
+        let mut mailto = UnsubscribeMailto {
+            to: EmailAddress::parse(&decode(address))?,
+            subject: None,
+            body: None,
+        };
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            if key.eq_ignore_ascii_case("subject") {
+                mailto.subject = Some(decode(value));
+            } else if key.eq_ignore_ascii_case("body") {
+                mailto.body = Some(decode(value));
+            }
+        }
+        Some(mailto)
+    }
+}
+
+/// Unsubscribe request to send, from a List-Unsubscribe `mailto:` URL
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UnsubscribeMailto {
+    /// Recipient of the unsubscribe request
+    pub to: EmailAddress,
+
+    /// Subject from the `subject` parameter
+    pub subject: Option<String>,
+
+    /// Body from the `body` parameter
+    pub body: Option<String>,
+}
+
 /// Email priority level
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Priority {
@@ -394,10 +2030,131 @@ impl Priority {
             _ => Self::Normal,
         }
     }
+
+    /// Map an `Importance` header value (`high`, `normal`, `low`)
+    #[must_use]
+    pub fn from_importance(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "high" => Self::High,
+            "low" => Self::Low,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// Value of the Precedence header
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Precedence {
+    Bulk,
+    List,
+    Junk,
+}
+
+impl Precedence {
+    /// Parse a Precedence value, ignoring unknown values like `first-class`
+    #[must_use]
+    pub fn from_header(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "bulk" => Some(Self::Bulk),
+            "list" => Some(Self::List),
+            "junk" => Some(Self::Junk),
+            _ => None,
+        }
+    }
+}
+
+/// Value of the Auto-Submitted header (RFC 3834)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AutoSubmitted {
+    /// Explicitly sent by a person
+    No,
+    /// Generated by a process, not in response to a message
+    AutoGenerated,
+    /// Automatic response to a message, such as a vacation notice
+    AutoReplied,
+    /// Notification such as a delivery status report (RFC 3798)
+    AutoNotified,
+    /// Extension value, lowercased
+    Other(String),
+}
+
+impl AutoSubmitted {
+    /// Parse an Auto-Submitted value, ignoring its parameters
+    #[must_use]
+    pub fn from_header(value: &str) -> Option<Self> {
+        let keyword = value.split(';').next().unwrap_or_default().trim();
+        match keyword.to_ascii_lowercase().as_str() {
+            "" => None,
+            "no" => Some(Self::No),
+            "auto-generated" => Some(Self::AutoGenerated),
+            "auto-replied" => Some(Self::AutoReplied),
+            "auto-notified" => Some(Self::AutoNotified),
+            other => Some(Self::Other(other.to_string())),
+        }
+    }
+
+    /// Any value other than `no` marks the message as automatic
+    #[must_use]
+    pub fn is_automated(&self) -> bool {
+        *self != Self::No
+    }
+}
+
+/// Brand Indicators for Message Identification (BIMI) header values
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BimiInfo {
+    /// Selector from `BIMI-Selector` (`s=` tag)
+    pub selector: Option<String>,
+
+    /// Logo URL from `BIMI-Location` (`l=` tag)
+    pub location: Option<String>,
+
+    /// Verified mark certificate URL from `BIMI-Location` (`a=` tag)
+    pub authority: Option<String>,
+}
+
+/// Campaign identifiers stamped by marketing automation platforms
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CampaignInfo {
+    /// Sending platform, when recognisable from its headers
+    pub platform: Option<CampaignPlatform>,
+
+    /// Campaign identifier (`X-Mailchimp-Campaign`, `X-Campaign-ID`,
+    /// `X-Mailgun-Campaign-Id`, ...)
+    pub campaign_id: Option<String>,
+
+    /// Per-message identifier assigned by the platform (`X-SG-EID`,
+    /// `X-Mailgun-Sid`, ...)
+    pub message_id: Option<String>,
+
+    /// Raw `Feedback-ID` header used for feedback loop reporting
+    pub feedback_id: Option<String>,
+}
+
+/// Marketing automation or bulk sending platform
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CampaignPlatform {
+    Mailchimp,
+    SendGrid,
+    Mailgun,
+    AmazonSes,
+    Other(String),
+}
+
+impl fmt::Display for CampaignPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mailchimp => write!(f, "Mailchimp"),
+            Self::SendGrid => write!(f, "SendGrid"),
+            Self::Mailgun => write!(f, "Mailgun"),
+            Self::AmazonSes => write!(f, "Amazon SES"),
+            Self::Other(name) => write!(f, "{name}"),
+        }
+    }
 }
 
 /// Email authentication results
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AuthenticationResults {
     /// SPF result
     pub spf: Option<AuthResult>,
@@ -419,12 +2176,270 @@ pub enum AuthResult {
     Unknown(String),
 }
 
+/// Headers of the original message quoted in an inline forward
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ForwardedHeaders {
+    /// Original sender
+    pub from: Option<EmailAddress>,
+
+    /// Original primary recipients
+    pub to: Vec<EmailAddress>,
+
+    /// Original CC recipients
+    pub cc: Vec<EmailAddress>,
+
+    /// Original subject
+    pub subject: Option<String>,
+
+    /// Original date as written by the forwarding client
+    pub date: Option<String>,
+}
+
+/// Result of checking a message's MIME structure
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StructureHealth {
+    /// Problems in the order they were found, depth-first
+    pub issues: Vec<StructureIssue>,
+}
+
+impl StructureHealth {
+    /// Check whether no structural problems were found
+    #[must_use]
+    pub const fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A structural problem in a MIME message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StructureIssue {
+    /// A `multipart/*` part has no `boundary` parameter
+    MissingBoundary,
+    /// The declared boundary never occurs in the part
+    BoundaryNotFound(String),
+    /// The closing `--boundary--` delimiter is missing
+    UnterminatedMultipart(String),
+    /// A `multipart/*` part yielded no subparts
+    MultipartWithoutParts,
+    /// A `text/*` part has no content
+    EmptyTextPart,
+}
+
+/// Event from an iCalendar (`text/calendar`) part
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CalendarEvent {
+    /// iTIP method (METHOD property or Content-Type `method` parameter)
+    pub method: Option<CalendarMethod>,
+
+    /// Event UID, stable across updates and cancellations
+    pub uid: Option<String>,
+
+    /// Event title
+    pub summary: Option<String>,
+
+    /// Event location
+    pub location: Option<String>,
+
+    /// DTSTART value as written (e.g. `20250106T100000Z`)
+    pub start: Option<String>,
+
+    /// DTEND value as written
+    pub end: Option<String>,
+
+    /// STATUS value, uppercased (e.g. `CONFIRMED`, `CANCELLED`)
+    pub status: Option<String>,
+
+    /// SEQUENCE number, incremented on each update
+    pub sequence: Option<u32>,
+
+    /// Organizer address
+    pub organizer: Option<String>,
+
+    /// Attendee addresses
+    pub attendees: Vec<String>,
+}
+
+impl CalendarEvent {
+    /// Check whether this cancels a previously sent event
+    #[must_use]
+    pub fn is_cancellation(&self) -> bool {
+        self.method == Some(CalendarMethod::Cancel) || self.status.as_deref() == Some("CANCELLED")
+    }
+
+    /// Check whether this is an invitation (new or updated)
+    #[must_use]
+    pub fn is_request(&self) -> bool {
+        self.method == Some(CalendarMethod::Request) && !self.is_cancellation()
+    }
+
+    /// Check whether this updates an earlier invitation (SEQUENCE above 0)
+    #[must_use]
+    pub fn is_update(&self) -> bool {
+        self.is_request() && self.sequence.is_some_and(|seq| seq > 0)
+    }
+}
+
+/// Signing and encryption detected on a message
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecurityInfo {
+    /// `multipart/signed` structure or an inline PGP signed message
+    pub signed: bool,
+
+    /// `multipart/encrypted` or S/MIME enveloped structure, or an inline PGP
+    /// message
+    pub encrypted: bool,
+
+    /// PGP armor pasted into the body text rather than MIME-wrapped
+    pub inline_pgp: bool,
+}
+
+/// Per-recipient report of a delivery status notification (RFC 3464)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeliveryStatus {
+    /// Final-Recipient address, without the `rfc822;` type
+    pub recipient: String,
+
+    /// Original-Recipient address, if reported
+    pub original_recipient: Option<String>,
+
+    /// Action field, lowercased (`failed`, `delayed`, `delivered`, ...)
+    pub action: Option<String>,
+
+    /// Enhanced status code (`5.1.1`), from the Status field or, failing
+    /// that, the diagnostic text
+    pub status: Option<String>,
+
+    /// Diagnostic-Code text from the remote server, without the `smtp;` type
+    pub diagnostic_code: Option<String>,
+
+    /// Remote-MTA that reported the failure
+    pub remote_mta: Option<String>,
+
+    /// Category of the status code
+    pub reason: BounceReason,
+
+    /// Hard or soft bounce; `None` when the report is not a failure
+    pub bounce: Option<BounceType>,
+}
+
+impl DeliveryStatus {
+    /// Check whether the address should not be retried
+    #[must_use]
+    pub fn is_hard_bounce(&self) -> bool {
+        self.bounce == Some(BounceType::Hard)
+    }
+}
+
+/// Reason for a failed delivery, from the subject and detail of an
+/// enhanced status code (RFC 3463)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BounceReason {
+    /// X.1.1 / X.1.10: mailbox does not exist
+    UnknownUser,
+    /// X.1.2: destination domain does not exist
+    UnknownDomain,
+    /// X.1.3: malformed address
+    InvalidAddress,
+    /// X.2.1: mailbox disabled
+    MailboxDisabled,
+    /// X.2.2: mailbox full or over quota
+    MailboxFull,
+    /// X.2.3 / X.3.4: message too large
+    MessageTooLarge,
+    /// X.4.7: delivery time expired
+    Timeout,
+    /// Other X.4.X network and routing errors
+    RoutingError,
+    /// X.7.X: rejected by security or spam policy
+    PolicyRejection,
+    /// Anything else, or no status code
+    Other,
+}
+
+impl fmt::Display for BounceReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnknownUser => "unknown-user",
+            Self::UnknownDomain => "unknown-domain",
+            Self::InvalidAddress => "invalid-address",
+            Self::MailboxDisabled => "mailbox-disabled",
+            Self::MailboxFull => "mailbox-full",
+            Self::MessageTooLarge => "message-too-large",
+            Self::Timeout => "timeout",
+            Self::RoutingError => "routing-error",
+            Self::PolicyRejection => "policy-rejection",
+            Self::Other => "other",
+        })
+    }
+}
+
+/// Whether a bounce is permanent or worth retrying
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BounceType {
+    Hard,
+    Soft,
+}
+
+/// Contact card from a vCard (`text/vcard`) part
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VCard {
+    /// Formatted name (FN)
+    pub name: Option<String>,
+
+    /// EMAIL values
+    pub emails: Vec<String>,
+
+    /// TEL values, without a `tel:` scheme
+    pub phones: Vec<String>,
+
+    /// ORG value, with organizational units joined by `, `
+    pub organization: Option<String>,
+
+    /// Job title (TITLE)
+    pub title: Option<String>,
+}
+
+/// iTIP scheduling method (RFC 5546)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CalendarMethod {
+    Publish,
+    Request,
+    Reply,
+    Add,
+    Cancel,
+    Refresh,
+    Counter,
+    DeclineCounter,
+    Other(String),
+}
+
+impl CalendarMethod {
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "PUBLISH" => Self::Publish,
+            "REQUEST" => Self::Request,
+            "REPLY" => Self::Reply,
+            "ADD" => Self::Add,
+            "CANCEL" => Self::Cancel,
+            "REFRESH" => Self::Refresh,
+            "COUNTER" => Self::Counter,
+            "DECLINECOUNTER" => Self::DeclineCounter,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
 /// Thread information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ThreadInfo {
-    /// In-Reply-To header (message ID of parent)
+    /// Parent message ID from In-Reply-To; the last one when the header
+    /// lists several, matching the direct parent at the end of References
     pub in_reply_to: Option<MessageId>,
 
+    /// Every message ID listed in In-Reply-To, in header order
+    pub in_reply_to_all: Vec<MessageId>,
+
     /// References header (list of message IDs in thread)
     pub references: Vec<MessageId>,
 
@@ -433,10 +2448,19 @@ pub struct ThreadInfo {
 
     /// Estimated position in thread
     pub thread_position: u32,
+
+    /// Thread-Topic header (Exchange, usually the un-prefixed subject)
+    pub topic: Option<String>,
+
+    /// Raw Thread-Index header (Exchange, base64)
+    pub thread_index: Option<String>,
+
+    /// Conversation GUID decoded from Thread-Index
+    pub conversation_id: Option<String>,
 }
 
 /// Email metadata and analysis results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct EmailMetadata {
     /// Spam indicators score (0.0 = clean, 1.0 = spam)
     pub spam_score: f32,
@@ -458,32 +2482,81 @@ pub struct EmailMetadata {
 
     /// Sentiment hints (positive, negative, neutral)
     pub sentiment: Sentiment,
+
+    /// Urgency from subject keywords alone, ignoring priority headers
+    pub subject_urgency: Urgency,
+
+    /// Sentiment of the subject alone (`sentiment` covers the body)
+    pub subject_sentiment: Sentiment,
 }
 
 /// Spam indicator
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SpamIndicator {
     pub indicator: String,
     pub weight: f32,
 }
 
 /// Urgency level
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Urgency {
     Critical,
     High,
+    #[default]
     Normal,
     Low,
 }
 
 /// Category hint for email classification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CategoryHint {
     pub category: String,
     pub confidence: f32,
     pub reason: String,
 }
 
+/// Routing intent derived by `Email::intent()`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Intent {
+    /// Asks the recipient to answer or act
+    Request,
+    /// Receipt, order or account confirmation
+    Confirmation,
+    /// Customer complaint or escalation
+    Complaint,
+    /// Marketing or cold sales outreach
+    SalesOutreach,
+    /// Automated notification
+    Notification,
+    /// Informational message needing no action
+    Fyi,
+}
+
+/// Order or shipment stage derived by `Email::order_status()`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OrderStatus {
+    /// Order received and confirmed
+    Confirmed,
+    /// Payment received or confirmed
+    PaymentReceived,
+    /// Order being prepared
+    Processing,
+    /// Handed to the carrier
+    Shipped,
+    /// On the delivery vehicle
+    OutForDelivery,
+    /// Delivered to the recipient
+    Delivered,
+    /// Delivery attempted without success
+    DeliveryFailed,
+    /// Order cancelled
+    Cancelled,
+    /// Money returned to the customer
+    Refunded,
+    /// Returned item received by the merchant
+    Returned,
+}
+
 /// Sentiment analysis result
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Sentiment {
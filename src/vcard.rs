@@ -0,0 +1,113 @@
+//! Minimal vCard (RFC 2426 / RFC 6350) parsing for shared contacts
+
+use crate::calendar::{unescape, unfold};
+use crate::mime;
+use crate::types::VCard;
+
+/// MIME types used for vCard parts
+const VCARD_TYPES: [&str; 3] = ["text/vcard", "text/x-vcard", "text/directory"];
+
+/// Collect the contacts from every vCard part, including `.vcf` attachments
+/// sent with a generic content type
+pub fn find_vcards(parsed: &mailparse::ParsedMail) -> Vec<VCard> {
+    if !parsed.subparts.is_empty() {
+        return parsed.subparts.iter().flat_map(find_vcards).collect();
+    }
+
+    let mimetype = parsed.ctype.mimetype.to_lowercase();
+    let disposition = parsed.get_content_disposition();
+    let is_vcf_file = disposition
+        .params
+        .get("filename")
+        .or_else(|| parsed.ctype.params.get("name"))
+        .is_some_and(|name| name.to_lowercase().ends_with(".vcf"));
+    if !VCARD_TYPES.contains(&mimetype.as_str()) && !is_vcf_file {
+        return Vec::new();
+    }
+
+    parsed.get_body_raw().map_or_else(
+        |_| Vec::new(),
+        |raw| parse_vcards(&mime::decode_charset_lossy(&raw, &parsed.ctype.charset)),
+    )
+}
+
+/// Parse FN, EMAIL, TEL, ORG and TITLE from each BEGIN:VCARD block
+pub fn parse_vcards(text: &str) -> Vec<VCard> {
+    let mut cards = Vec::new();
+    let mut current: Option<VCard> = None;
+
+    for line in unfold(text) {
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name_params.split(';').next().unwrap_or_default();
+        // Drop the optional group prefix (`item1.EMAIL`)
+        let name = name
+            .rsplit('.')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let value = value.trim();
+
+        match name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VCARD") => current = Some(VCard::default()),
+            "END" if value.eq_ignore_ascii_case("VCARD") => cards.extend(current.take()),
+            _ => {}
+        }
+        let Some(ref mut card) = current else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+
+        match name.as_str() {
+            "FN" => card.name = Some(unescape(value)),
+            "EMAIL" => card.emails.push(strip_scheme(value, "mailto:")),
+            "TEL" => card.phones.push(strip_scheme(value, "tel:")),
+            "ORG" => {
+                // Organization name followed by unit names, `;`-separated
+                let units: Vec<String> = split_components(value)
+                    .into_iter()
+                    .filter(|unit| !unit.is_empty())
+                    .collect();
+                if !units.is_empty() {
+                    card.organization = Some(units.join(", "));
+                }
+            }
+            "TITLE" => card.title = Some(unescape(value)),
+            _ => {}
+        }
+    }
+
+    cards
+}
+
+/// Split a structured value on unescaped `;`, unescaping each component
+fn split_components(value: &str) -> Vec<String> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => {
+                components.push(unescape(value[start..i].trim()));
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    components.push(unescape(value[start..].trim()));
+    components
+}
+
+/// Strip a URI scheme used by vCard 4.0 values (`tel:`, `mailto:`)
+fn strip_scheme(value: &str, scheme: &str) -> String {
+    value
+        .get(..scheme.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        .map_or(value, |_| &value[scheme.len()..])
+        .to_string()
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "csv")]
+
+use email_extract::{parse_email, write_entities_csv, write_entities_tsv};
+
+fn sample() -> Vec<email_extract::Email> {
+    let raw = b"From: sender@example.com\r\n\
+                Message-ID: <csv@example.com>\r\n\
+                \r\n\
+                Reach john@company.com or visit https://company.com";
+    vec![parse_email(7, raw).unwrap()]
+}
+
+#[test]
+fn test_write_entities_csv() {
+    let mut out = Vec::new();
+    write_entities_csv(&sample(), &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    let mut lines = csv.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("uid,message_id,entity_type,value,context")
+    );
+    assert!(csv.contains("7,<csv@example.com>,email,john@company.com,Reach john@company.com or"));
+    assert!(csv.contains("7,<csv@example.com>,url,https://company.com,\n"));
+}
+
+#[test]
+fn test_write_entities_tsv() {
+    let mut out = Vec::new();
+    write_entities_tsv(&sample(), &mut out).unwrap();
+    let tsv = String::from_utf8(out).unwrap();
+
+    assert!(tsv.starts_with("uid\tmessage_id\tentity_type\tvalue\tcontext\n"));
+    assert!(tsv.contains("7\t<csv@example.com>\turl\thttps://company.com\t\n"));
+}
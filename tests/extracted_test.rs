@@ -1,3 +1,5 @@
+#![allow(clippy::float_cmp)]
+
 use email_extract::*;
 
 #[test]
@@ -18,6 +20,139 @@ fn test_extract_phone() {
     assert_eq!(entities.phone_numbers.len(), 2);
 }
 
+#[test]
+fn test_extract_phone_rejects_short_numbers() {
+    let text = "Ticket 555-1234 was opened";
+    let entities = ExtractedEntities::extract(text);
+
+    assert!(entities.phone_numbers.is_empty());
+}
+
+#[test]
+fn test_extract_phone_rejects_bare_integers() {
+    let text = "Order 5551234567 has shipped";
+    let entities = ExtractedEntities::extract(text);
+
+    assert!(entities.phone_numbers.is_empty());
+}
+
+#[test]
+fn test_extract_phone_min_digits_configurable() {
+    let options = ExtractionOptions {
+        min_phone_digits: 7,
+        ..ExtractionOptions::default()
+    };
+    let entities = ExtractedEntities::extract_with_options("Call 555-1234", &options);
+
+    assert_eq!(entities.phone_numbers.len(), 1);
+    assert_eq!(entities.phone_numbers[0].normalized, "5551234");
+}
+
+#[test]
+fn test_custom_patterns() {
+    let options = ExtractionOptions {
+        email_pattern: Some(regex::Regex::new(r"[a-z0-9.]+@corp\.example").unwrap()),
+        phone_pattern: Some(regex::Regex::new(r"\+49 \d{2,4} \d{6,8}").unwrap()),
+        url_pattern: Some(regex::Regex::new(r"https://\S+").unwrap()),
+        ..ExtractionOptions::default()
+    };
+    let text = "Mail jo@corp.example or jo@gmail.com, call +49 30 1234567, \
+                see https://corp.example/a and http://corp.example/b";
+    let entities = ExtractedEntities::extract_with_options(text, &options);
+
+    assert_eq!(entities.emails.len(), 1);
+    assert_eq!(entities.emails[0].address, "jo@corp.example");
+    assert_eq!(entities.phone_numbers.len(), 1);
+    assert_eq!(entities.phone_numbers[0].normalized, "+49301234567");
+    assert_eq!(entities.urls.len(), 1);
+    assert_eq!(entities.urls[0].url, "https://corp.example/a");
+}
+
+#[test]
+fn test_extract_phone_default_region() {
+    let options = ExtractionOptions {
+        default_region: Some("gb".into()),
+        ..ExtractionOptions::default()
+    };
+    let text = "Call 020 7946 0958, mobile 07700 900123 or our US office +1 212-555-0147";
+    let entities = ExtractedEntities::extract_with_options(text, &options);
+
+    let phones: Vec<(&str, Option<&str>, PhoneType)> = entities
+        .phone_numbers
+        .iter()
+        .map(|p| {
+            (
+                p.normalized.as_str(),
+                p.country_code.as_deref(),
+                p.phone_type.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        phones,
+        vec![
+            ("+442079460958", Some("GB"), PhoneType::Landline),
+            ("+447700900123", Some("GB"), PhoneType::Mobile),
+            ("+12125550147", Some("US"), PhoneType::Unknown),
+        ]
+    );
+
+    // NANP regions keep the NANP pattern and gain the +1 prefix
+    let us = ExtractionOptions {
+        default_region: Some("US".into()),
+        ..ExtractionOptions::default()
+    };
+    let entities = ExtractedEntities::extract_with_options("Call (212) 555-0147", &us);
+    assert_eq!(entities.phone_numbers[0].normalized, "+12125550147");
+    assert_eq!(
+        entities.phone_numbers[0].country_code.as_deref(),
+        Some("US")
+    );
+}
+
+#[test]
+fn test_single_type_extractors() {
+    let text = "Mail john@example.com, call (555) 123-4567, \
+                pay $19.99 at https://example.com/pay";
+    let all = ExtractedEntities::extract(text);
+
+    assert_eq!(extract_emails(text), all.emails);
+    assert_eq!(extract_phone_numbers(text), all.phone_numbers);
+    assert_eq!(extract_urls(text), all.urls);
+    assert_eq!(extract_amounts(text), all.amounts);
+    assert_eq!(extract_phone_numbers(text).len(), 1);
+    assert!(extract_emails("no address here").is_empty());
+}
+
+#[test]
+fn test_extract_times() {
+    let text = "Let's meet at 3pm EST, or 10:30 a.m. tomorrow. \
+                The call is at 14:00 UTC+2 and ends by 12am.";
+    let entities = ExtractedEntities::extract(text);
+
+    let times: Vec<(u8, u8, Option<&str>)> = entities
+        .times
+        .iter()
+        .map(|t| (t.hour, t.minute, t.timezone.as_deref()))
+        .collect();
+    assert_eq!(
+        times,
+        vec![
+            (15, 0, Some("EST")),
+            (10, 30, None),
+            (14, 0, Some("UTC+2")),
+            (0, 0, None),
+        ]
+    );
+    assert_eq!(entities.times[0].raw, "3pm EST");
+
+    assert!(
+        ExtractedEntities::extract("Version 2.3, ratio 3:1, $10.30")
+            .times
+            .is_empty()
+    );
+}
+
 #[test]
 fn test_extract_urls() {
     let text = "Visit https://example.com or \
@@ -28,6 +163,28 @@ fn test_extract_urls() {
     assert_eq!(entities.urls[1].url_type, UrlType::SocialMedia);
 }
 
+#[test]
+fn test_extract_survey_urls() {
+    let text = "Rate us: https://www.surveymonkey.com/r/ABC123 \
+                https://acme.typeform.com/to/xyz \
+                https://shop.example/nps/vote?nps_score=9 \
+                https://docs.google.com/forms/d/e/1FAIpQ/viewform \
+                https://shop.example/products";
+    let entities = ExtractedEntities::extract(text);
+
+    let types: Vec<UrlType> = entities.urls.iter().map(|u| u.url_type.clone()).collect();
+    assert_eq!(
+        types,
+        vec![
+            UrlType::Survey,
+            UrlType::Survey,
+            UrlType::Survey,
+            UrlType::Survey,
+            UrlType::Website
+        ]
+    );
+}
+
 #[test]
 fn test_extract_amounts() {
     let text = "The price is $1,500.00 or €2000";
@@ -50,3 +207,421 @@ fn test_tracking_url_detection() {
     assert!(entities.urls[1].is_tracking);
     assert!(!entities.urls[2].is_tracking);
 }
+
+#[test]
+fn test_cleaned_url_strips_tracking_params() {
+    let text = "See https://shop.example/item?id=42&utm_source=email&UTM_Medium=x&fbclid=abc#reviews \
+                and https://shop.example/?gclid=1&mc_eid=2 \
+                and https://shop.example/plain";
+    let entities = ExtractedEntities::extract(text);
+
+    let cleaned: Vec<String> = entities.urls.iter().map(ExtractedUrl::cleaned).collect();
+    assert_eq!(
+        cleaned,
+        vec![
+            "https://shop.example/item?id=42#reviews",
+            "https://shop.example/",
+            "https://shop.example/plain",
+        ]
+    );
+}
+
+#[test]
+fn test_extraction_toggles() {
+    let text = "Mail john@example.com, call (555) 123-4567, \
+                see https://example.com, pay $20.00, ping @johndoe";
+    let options = ExtractionOptions {
+        extract_emails: false,
+        extract_phones: false,
+        extract_social: false,
+        ..ExtractionOptions::default()
+    };
+    let entities = ExtractedEntities::extract_with_options(text, &options);
+
+    assert!(entities.emails.is_empty());
+    assert!(entities.phone_numbers.is_empty());
+    assert!(entities.social_handles.is_empty());
+    assert_eq!(entities.urls.len(), 1);
+    assert_eq!(entities.amounts.len(), 1);
+}
+
+#[test]
+fn test_confidence_rises_with_context() {
+    let labelled = ExtractedEntities::extract("Phone: (555) 123-4567");
+    let bare = ExtractedEntities::extract("Ref 555 123 4567");
+
+    assert!(labelled.phone_numbers[0].confidence > bare.phone_numbers[0].confidence);
+    assert!(labelled.phone_numbers[0].confidence <= 1.0);
+}
+
+#[test]
+fn test_confidence_handle_inside_email_is_low() {
+    let entities = ExtractedEntities::extract("Write to john@example.com or follow @acme");
+    let inside_email = &entities.social_handles[0];
+    let mention = &entities.social_handles[1];
+
+    assert_eq!(inside_email.handle, "example");
+    assert_eq!(mention.handle, "acme");
+    assert!(inside_email.confidence < mention.confidence);
+}
+
+#[test]
+fn test_confidence_amount_keyword() {
+    let entities = ExtractedEntities::extract("Total due: $42.00, code 7 USD");
+
+    assert!(entities.amounts[0].confidence > entities.amounts[1].confidence);
+}
+
+#[test]
+fn test_extract_companies_international() {
+    let text = "Best regards,\nJohn Doe\nAcme Widgets GmbH\n\
+                Partner: Dupont Freres SARL and Rossi Trasporti S.p.A.\n\
+                Billing via Muller GmbH & Co. KG, US office Acme, Inc.";
+
+    let entities = ExtractedEntities::extract(text);
+
+    assert_eq!(
+        entities.companies,
+        vec![
+            "Acme Widgets GmbH",
+            "Dupont Freres SARL",
+            "Rossi Trasporti S.p.A.",
+            "Muller GmbH & Co. KG",
+            "Acme, Inc.",
+        ]
+    );
+}
+
+#[test]
+fn test_extract_companies_short_forms() {
+    let text = "Thanks to The AB and Team SA for the call with the CO.\n\
+                Ag. Smith Co met Sa Ventures.\n\
+                Orders from Acme Co., Dupont S.A. and Volvo Cars AB.";
+
+    let entities = ExtractedEntities::extract(text);
+
+    assert_eq!(
+        entities.companies,
+        vec!["Acme Co.", "Dupont S.A.", "Volvo Cars AB"]
+    );
+}
+
+#[test]
+fn test_extract_companies_one_word_short_forms() {
+    let text = "Siemens AG, Volkswagen AG and Nestlé SA signed.\n\
+                The Airbus SE board met. Our AB test ran.";
+
+    let entities = ExtractedEntities::extract(text);
+
+    assert_eq!(
+        entities.companies,
+        vec!["Siemens AG", "Volkswagen AG", "Nestlé SA", "Airbus SE"]
+    );
+}
+
+#[test]
+fn test_extract_companies_nested_suffixes() {
+    let entities = ExtractedEntities::extract("Invoice from Big Corp Ltd and Acme Corp.");
+
+    assert_eq!(entities.companies, vec!["Big Corp Ltd", "Acme Corp."]);
+}
+
+#[test]
+fn test_extract_companies_custom_suffixes() {
+    let options = ExtractionOptions {
+        company_suffixes: vec!["KK".into()],
+        ..ExtractionOptions::default()
+    };
+
+    let entities = ExtractedEntities::extract_with_options("Sony Group KK and Acme GmbH", &options);
+
+    assert_eq!(entities.companies, vec!["Sony Group KK"]);
+}
+
+#[test]
+fn test_amount_labels() {
+    let text = "Subtotal: $40.00, shipping fee $5.00. Refund of 12.50 EUR issued.\n\
+                Flight AA 2000 USD reference";
+
+    let entities = ExtractedEntities::extract(text);
+    let labels: Vec<Option<&str>> = entities
+        .amounts
+        .iter()
+        .map(|a| a.label.as_deref())
+        .collect();
+
+    assert_eq!(
+        labels,
+        vec![Some("subtotal"), Some("fee"), Some("refund"), None]
+    );
+}
+
+#[test]
+fn test_amounts_require_context() {
+    let options = ExtractionOptions {
+        require_amount_context: true,
+        ..ExtractionOptions::default()
+    };
+
+    let entities =
+        ExtractedEntities::extract_with_options("Flight AA 2000 USD. Total: $99.00", &options);
+
+    assert_eq!(entities.amounts.len(), 1);
+    assert_eq!(entities.amounts[0].raw, "$99.00");
+    assert_eq!(entities.amounts[0].label.as_deref(), Some("total"));
+}
+
+#[test]
+fn test_extract_companies_requires_name() {
+    let entities = ExtractedEntities::extract("we are an LLC based in Ohio");
+
+    assert!(entities.companies.is_empty());
+}
+
+#[test]
+fn test_email_inside_url_dropped() {
+    let text = "Profile: https://example.com/users/john@example.com and mailto:jane@example.com";
+
+    let entities = ExtractedEntities::extract(text);
+
+    assert_eq!(entities.emails.len(), 1);
+    assert_eq!(entities.emails[0].address, "jane@example.com");
+    assert!((entities.emails[0].confidence - 1.0).abs() < f32::EPSILON);
+    assert_eq!(entities.urls.len(), 1);
+}
+
+#[test]
+fn test_email_after_multibyte_text() {
+    let entities = ExtractedEntities::extract("日本語john@example.com");
+
+    assert_eq!(entities.emails.len(), 1);
+    assert!(entities.emails[0].address.ends_with("john@example.com"));
+}
+
+#[test]
+fn test_email_inside_url_kept_when_overlapping() {
+    let options = ExtractionOptions {
+        keep_overlapping: true,
+        ..ExtractionOptions::default()
+    };
+
+    let entities = ExtractedEntities::extract_with_options(
+        "See https://example.com/users/john@example.com",
+        &options,
+    );
+
+    assert_eq!(entities.emails.len(), 1);
+    assert!(entities.emails[0].in_url);
+}
+
+#[test]
+fn test_extract_order_numbers() {
+    let text = "Thanks! Order #A1B2-3456 is confirmed.\n\
+                Invoice No. INV-2025-001 attached.\n\
+                Confirmation number: 7Z9QX";
+
+    let entities = ExtractedEntities::extract(text);
+    let found: Vec<(&str, &str)> = entities
+        .order_numbers
+        .iter()
+        .map(|r| (r.label.as_str(), r.value.as_str()))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            ("order", "A1B2-3456"),
+            ("invoice", "INV-2025-001"),
+            ("confirmation", "7Z9QX"),
+        ]
+    );
+    assert_eq!(
+        &text[entities.order_numbers[0].position..][..9],
+        "A1B2-3456"
+    );
+}
+
+#[test]
+fn test_order_numbers_need_digits_and_label() {
+    let entities = ExtractedEntities::extract("Your order is confirmed. Product code ABC123.");
+
+    assert!(entities.order_numbers.is_empty());
+}
+
+#[test]
+fn test_extract_quantities() {
+    let text =
+        "Your order:\n2 x Blue Widget\nGadget Pro Qty: 10\nShipping 5 kg of flour in 3 parcels";
+
+    let entities = ExtractedEntities::extract(text);
+    let found: Vec<(f64, Option<&str>, Option<&str>)> = entities
+        .quantities
+        .iter()
+        .map(|q| (q.value, q.unit.as_deref(), q.item.as_deref()))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            (2.0, None, Some("Blue Widget")),
+            (10.0, None, Some("Gadget Pro")),
+            (5.0, Some("kg"), Some("flour")),
+            (3.0, Some("parcels"), None),
+        ]
+    );
+}
+
+#[test]
+fn test_quantities_ignore_bare_numbers() {
+    let entities = ExtractedEntities::extract("Meeting at 10 in room 4, call 3 times");
+
+    assert!(entities.quantities.is_empty());
+}
+
+#[test]
+fn test_extract_line_items() {
+    let text = "Order summary\n\
+                Widget x2 \u{2014} $19.98\n\
+                3 x Gadget Pro @ $5.00\n\
+                Cable\t2\t$4.50\t$9.00\n\
+                Subtotal: $43.98\n\
+                Shipping: $5.00\n\
+                Total: $48.98";
+
+    let entities = ExtractedEntities::extract(text);
+    let found: Vec<(&str, f64, Option<f64>, f64)> = entities
+        .line_items
+        .iter()
+        .map(|i| (i.description.as_str(), i.quantity, i.unit_price, i.total))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            ("Widget", 2.0, Some(9.99), 19.98),
+            ("Gadget Pro", 3.0, Some(5.0), 15.0),
+            ("Cable", 2.0, Some(4.5), 9.0),
+        ]
+    );
+    assert!(entities.line_items.iter().all(|i| i.currency == "USD"));
+}
+
+#[test]
+fn test_extract_ip_addresses() {
+    let text = "Login from 203.0.113.7 and 8.8.8.8, internal 192.168.1.20, \
+                v6 2606:4700::1111 and fe80::1. Running version 1.2.3.4.5, \
+                not an IP: 999.1.1.1 or 10:30:00.";
+
+    let entities = ExtractedEntities::extract(text);
+    let found: Vec<(String, bool)> = entities
+        .ip_addresses
+        .iter()
+        .map(|ip| (ip.address.to_string(), ip.is_public()))
+        .collect();
+
+    assert_eq!(
+        found,
+        vec![
+            ("203.0.113.7".to_string(), false),
+            ("8.8.8.8".to_string(), true),
+            ("192.168.1.20".to_string(), false),
+            ("2606:4700::1111".to_string(), true),
+            ("fe80::1".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn test_extract_ip_ignores_bare_colons() {
+    for text in [
+        "Options :: list",
+        "Menu ::: Settings",
+        "Bind to :: or 0:0::0 for all interfaces",
+    ] {
+        let entities = ExtractedEntities::extract(text);
+        assert!(entities.ip_addresses.is_empty(), "{text}");
+    }
+
+    let entities = ExtractedEntities::extract("Loopback ::1 answered");
+    assert_eq!(entities.ip_addresses.len(), 1);
+}
+
+#[test]
+fn test_extract_skips_absent_entity_types() {
+    let text = "Newsletter ".repeat(1000) + "Visit https://example.com/sale";
+
+    let entities = ExtractedEntities::extract(&text);
+
+    assert_eq!(entities.urls.len(), 1);
+    assert!(entities.emails.is_empty());
+    assert!(entities.phone_numbers.is_empty());
+    assert!(entities.amounts.is_empty());
+    assert!(entities.ip_addresses.is_empty());
+}
+
+#[test]
+fn test_url_is_secure() {
+    let entities = ExtractedEntities::extract("See https://example.com and http://example.org");
+
+    assert!(entities.urls[0].is_secure);
+    assert!(!entities.urls[1].is_secure);
+}
+
+#[test]
+fn test_extract_hashtags() {
+    let text = "Join us at #RustConf2025 and #rustconf2025! Tag #community_day.\n\
+                Colors: #fff #a1b2c3. Order #12345. Agenda: https://example.com/agenda#day2 \
+                Tom&#39;s pick";
+
+    let entities = ExtractedEntities::extract(text);
+
+    assert_eq!(entities.hashtags, vec!["RustConf2025", "community_day"]);
+}
+
+#[test]
+fn test_amount_currencies() {
+    let text = "Fees: 2,000 CHF, \u{a5}500, CHF 75.50, C$20.00, \u{20b9}999 and 10 SEK";
+
+    let entities = ExtractedEntities::extract(text);
+    let currencies: Vec<&str> = entities
+        .amounts
+        .iter()
+        .map(|a| a.currency.as_str())
+        .collect();
+
+    assert_eq!(currencies, vec!["CHF", "JPY", "CHF", "CAD", "INR", "SEK"]);
+    assert!((entities.amounts[0].value - 2000.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_entity_stream_matches_single_pass() {
+    use std::fmt::Write as _;
+
+    let mut text = String::new();
+    for i in 0..1000 {
+        write!(
+            text,
+            "Deal {i}: Kopfhörer für $49.99, 2x Widget. Order ORD-{i:05} from Acme Widgets GmbH.\n\
+             Visit https://shop.example.com/p/{i} or write to sales{i}@example.com #sale{}\n\n",
+            i % 7
+        )
+        .unwrap();
+    }
+    let options = ExtractionOptions::default();
+    let expected = ExtractedEntities::extract_with_options(&text, &options);
+
+    let mut stream = EntityStream::new(&options);
+    let chars: Vec<char> = text.chars().collect();
+    for chunk in chars.chunks(1000) {
+        stream.push(&chunk.iter().collect::<String>());
+    }
+    let mut streamed = stream.finish();
+
+    assert!(text.len() > 2 * 64 * 1024);
+    assert_eq!(streamed.emails.len(), 1000);
+    for (email, expected) in streamed.emails.iter_mut().zip(&expected.emails) {
+        assert_eq!(email.position, expected.position);
+        email.context.clone_from(&expected.context);
+    }
+    assert_eq!(streamed, expected);
+}
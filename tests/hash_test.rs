@@ -0,0 +1,58 @@
+#![cfg(feature = "hash")]
+
+use email_extract::parse_email;
+
+const RAW: &[u8] = b"From: Sender <Sender@Example.com>\r\n\
+                     Message-ID: <hash@example.com>\r\n\
+                     Subject: Weekly report\r\n\
+                     \r\n\
+                     Numbers are   up this week.\r\n";
+
+#[test]
+fn test_content_hash_is_stable_across_uids() {
+    let first = parse_email(1, RAW).unwrap();
+    let second = parse_email(2, RAW).unwrap();
+
+    let hash = first.content_hash();
+    assert_eq!(hash.len(), 64);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(hash, second.content_hash());
+}
+
+#[test]
+fn test_content_hash_ignores_whitespace_and_address_case() {
+    let raw = b"From: Sender <sender@example.com>\r\n\
+                Message-ID: <hash@example.com>\r\n\
+                Subject: Weekly report\r\n\
+                \r\n\
+                Numbers are up\r\n  this week.\r\n";
+
+    assert_eq!(
+        parse_email(1, raw).unwrap().content_hash(),
+        parse_email(1, RAW).unwrap().content_hash()
+    );
+}
+
+#[test]
+fn test_content_hash_changes_with_content() {
+    let raw = b"From: Sender <sender@example.com>\r\n\
+                Message-ID: <hash@example.com>\r\n\
+                Subject: Weekly report\r\n\
+                \r\n\
+                Numbers are down this week.\r\n";
+
+    assert_ne!(
+        parse_email(1, raw).unwrap().content_hash(),
+        parse_email(1, RAW).unwrap().content_hash()
+    );
+}
+
+#[test]
+fn test_content_hash_ignores_synthetic_message_id() {
+    let raw = b"From: sender@example.com\r\nSubject: Hi\r\n\r\nBody\r\n";
+
+    assert_eq!(
+        parse_email(1, raw).unwrap().content_hash(),
+        parse_email(2, raw).unwrap().content_hash()
+    );
+}
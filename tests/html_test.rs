@@ -0,0 +1,55 @@
+use email_extract::{HtmlOptions, html_to_text, html_to_text_with_options};
+
+#[test]
+fn test_html_to_text_block_elements() {
+    let text = html_to_text("<div>One</div><p>Two</p>Three<br>Four");
+    assert_eq!(text, "One\nTwo\nThree\nFour");
+}
+
+#[test]
+fn test_html_to_text_drops_script_and_style() {
+    let html = "<style>p { color: red; }</style><p>Visible</p>\
+                <script>alert('x');</script>";
+    assert_eq!(html_to_text(html), "Visible");
+}
+
+#[test]
+fn test_html_to_text_decodes_entities() {
+    let text = html_to_text("<p>&lt;b&gt; &quot;quoted&quot; &#39;it&#39;s&#39;&nbsp;ok</p>");
+    assert_eq!(text, "<b> \"quoted\" 'it's' ok");
+}
+
+#[test]
+fn test_html_to_text_does_not_double_decode() {
+    assert_eq!(html_to_text("<p>&amp;lt;tag&amp;gt;</p>"), "&lt;tag&gt;");
+}
+
+#[test]
+fn test_html_to_text_non_ascii() {
+    let text = html_to_text("<p>\u{130}stanbul caf\u{e9}</p><div>Stra\u{df}e</div>");
+    assert_eq!(text, "\u{130}stanbul caf\u{e9}\nStra\u{df}e");
+}
+
+#[test]
+fn test_html_to_text_tables_and_lists() {
+    let html = "<h2>Receipt</h2><table><tr><th>Item</th><th>Price</th></tr>\
+                <tr><td>Widget</td><td>$5.00</td></tr></table>\
+                <ul><li>Fast</li><li>Cheap</li></ul><blockquote>Quoted</blockquote>After";
+
+    assert_eq!(
+        html_to_text(html),
+        "Receipt\nItem\tPrice\nWidget\t$5.00\nFast\nCheap\nQuoted\nAfter"
+    );
+}
+
+#[test]
+fn test_html_to_text_custom_block_elements() {
+    let options = HtmlOptions {
+        block_elements: vec!["span".into()],
+        ..HtmlOptions::default()
+    };
+
+    let text = html_to_text_with_options("<span>One</span><p>Two</p>Three", &options);
+
+    assert_eq!(text, "One\nTwoThree");
+}
@@ -1,4 +1,13 @@
-use email_extract::parse_email;
+#![allow(clippy::single_char_pattern)]
+
+use chrono::{FixedOffset, TimeZone, Utc};
+use email_extract::{
+    AutoSubmitted, BodySource, BounceReason, BounceType, CalendarMethod, CampaignPlatform,
+    DomainInfo, DomainProfile, ExtractedEntities, HtmlOptions, Intent, MessageId, OrderStatus,
+    ParseOptions, Precedence, Priority, RecipientRole, Sentiment, SpamConfig, StructureIssue,
+    Urgency, UrgencyConfig, decode_modified_utf7, parse_email, parse_email_lossy,
+    parse_email_with_options, parse_headers_only,
+};
 
 #[test]
 fn test_parse_simple_email() {
@@ -91,3 +100,2610 @@ fn test_strip_html() {
     assert!(text.contains("World"));
     assert!(!text.contains("<"));
 }
+
+#[test]
+fn test_alternative_prefers_last_part() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: Test\r\n\
+                Message-ID: <alt@example.com>\r\n\
+                Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+                \r\n\
+                --b1\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Plain version\r\n\
+                --b1\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Rich version</p>\r\n\
+                --b1--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.text, "Plain version");
+    assert_eq!(email.body.primary_source(), BodySource::HtmlStripped);
+    assert_eq!(email.body.best_text(), "Rich version");
+}
+
+#[test]
+fn test_alternative_with_text_last() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: Test\r\n\
+                Message-ID: <alt2@example.com>\r\n\
+                Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+                \r\n\
+                --b1\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Rich version</p>\r\n\
+                --b1\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Plain version\r\n\
+                --b1--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.primary_source(), BodySource::Text);
+    assert_eq!(email.body.best_text(), "Plain version");
+    assert!(email.body.text_from_html.is_none());
+}
+
+#[test]
+fn test_missing_date_uses_injected_now() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: No date\r\n\
+                \r\n\
+                Body";
+    let now = Utc.with_ymd_and_hms(2025, 3, 1, 8, 30, 0).unwrap();
+    let options = ParseOptions {
+        now: Some(now),
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+
+    assert_eq!(email.date, now);
+}
+
+#[test]
+fn test_header_date_ignores_injected_now() {
+    let raw = b"From: sender@example.com\r\n\
+                Date: Wed, 01 Jan 2025 12:00:00 +0000\r\n\
+                \r\n\
+                Body";
+    let options = ParseOptions {
+        now: Some(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+
+    assert_eq!(
+        email.date,
+        Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn test_message_id_domain_mismatch_indicator() {
+    let raw = b"From: billing@bank.com\r\n\
+                Message-ID: <123@bulk-sender.net>\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.message_id_domain_matches(), Some(false));
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "messageid_domain_mismatch")
+    );
+}
+
+#[test]
+fn test_synthetic_message_id_not_flagged() {
+    let raw = b"From: billing@bank.com\r\n\
+                Subject: Statement\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.message_id_domain_matches(), None);
+    assert!(email.metadata.spam_indicators.is_empty());
+}
+
+#[test]
+fn test_date_keeps_original_offset() {
+    let raw = b"From: sender@example.com\r\n\
+                Date: Wed, 01 Jan 2025 09:00:00 -0500\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(
+        email.date,
+        Utc.with_ymd_and_hms(2025, 1, 1, 14, 0, 0).unwrap()
+    );
+    assert_eq!(email.date_offset(), FixedOffset::west_opt(5 * 3600));
+    assert_eq!(
+        email.original_date.unwrap().to_rfc2822(),
+        "Wed, 1 Jan 2025 09:00:00 -0500"
+    );
+}
+
+#[test]
+fn test_missing_date_has_no_offset() {
+    let raw = b"From: sender@example.com\r\n\r\nBody";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.original_date.is_none());
+    assert!(email.date_offset().is_none());
+}
+
+#[test]
+fn test_tracking_pixel_detection() {
+    let raw = b"From: news@shop.com\r\n\
+                Message-ID: <px@shop.com>\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Sale!</p>\
+                <img src=\"https://shop.com/logo.png\" width=\"200\" height=\"50\">\
+                <img width=\"1\" height=\"1\" src=\"https://t.shop.com/open.gif\">\
+                <img src='https://t.shop.com/zero.gif' style='width:0px;height:0px'>\
+                <img src=\"https://t.shop.com/hidden.gif\" style=\"display: none\">";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(
+        email.extracted.tracking_pixels,
+        vec![
+            "https://t.shop.com/open.gif",
+            "https://t.shop.com/zero.gif",
+            "https://t.shop.com/hidden.gif",
+        ]
+    );
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "tracking_pixel")
+    );
+}
+
+#[test]
+fn test_image_only_body() {
+    let raw = b"From: promo@shop.com\r\n\
+                Subject: Offer\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <a href=\"https://shop.com/\"><img src=\"https://shop.com/offer.jpg\"></a>\
+                <img width=\"1\" height=\"1\" src=\"https://t.shop.com/open.gif\">";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.body.is_image_only());
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "image_only")
+    );
+
+    let pixel_only = b"From: friend@example.com\r\n\
+                       Subject: Hi\r\n\
+                       Content-Type: text/html\r\n\
+                       \r\n\
+                       <p>Hi</p><img width=\"1\" height=\"1\" src=\"https://t.example.com/o.gif\">";
+    assert!(!parse_email(2, pixel_only).unwrap().body.is_image_only());
+}
+
+fn tracking_email(count: usize) -> Vec<u8> {
+    let links: Vec<String> = (0..count)
+        .map(|i| format!("https://click.shop.com/track?id={i}"))
+        .collect();
+    format!(
+        "From: news@shop.com\r\nSubject: News\r\n\r\n{}",
+        links.join("\r\n")
+    )
+    .into_bytes()
+}
+
+fn tracking_weight(email: &email_extract::Email) -> Option<f32> {
+    email
+        .metadata
+        .spam_indicators
+        .iter()
+        .find(|i| i.indicator == "excessive_tracking")
+        .map(|i| i.weight)
+}
+
+#[test]
+fn test_excessive_tracking_graduated_weight() {
+    assert_eq!(
+        tracking_weight(&parse_email(1, &tracking_email(3)).unwrap()),
+        None
+    );
+
+    let few = tracking_weight(&parse_email(2, &tracking_email(4)).unwrap()).unwrap();
+    let many = tracking_weight(&parse_email(3, &tracking_email(30)).unwrap()).unwrap();
+    assert!((few - 0.2).abs() < 1e-6);
+    assert!(many > few);
+    assert!((many - 0.6).abs() < 1e-6);
+
+    let options = ParseOptions {
+        spam: SpamConfig {
+            tracking_threshold: 10,
+            ..SpamConfig::default()
+        },
+        ..ParseOptions::default()
+    };
+    let email = parse_email_with_options(4, &tracking_email(4), &options).unwrap();
+    assert_eq!(tracking_weight(&email), None);
+}
+
+#[test]
+fn test_trusted_domains_suppress_indicators() {
+    let raw = b"From: Newsletter <noreply@mail.partner.example>\r\n\
+                From: other@partner.example\r\n\
+                Subject: Urgent update\r\n\
+                \r\n\
+                Body";
+    let options = ParseOptions {
+        spam: SpamConfig {
+            trusted_domains: ["partner.example".to_string()].into(),
+            ..SpamConfig::default()
+        },
+        ..ParseOptions::default()
+    };
+
+    let untrusted = parse_email(1, raw).unwrap();
+    let trusted = parse_email_with_options(1, raw, &options).unwrap();
+
+    let names = |email: &email_extract::Email| -> Vec<String> {
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .map(|i| i.indicator.clone())
+            .collect()
+    };
+    assert!(names(&untrusted).contains(&"noreply_sender".to_string()));
+    assert!(names(&untrusted).contains(&"urgency_language".to_string()));
+    assert_eq!(names(&trusted), vec!["multiple_from"]);
+    assert!(trusted.metadata.spam_score < untrusted.metadata.spam_score);
+}
+
+#[test]
+fn test_exchange_thread_headers() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: RE: Budget\r\n\
+                Thread-Topic: Budget\r\n\
+                Thread-Index: AdECAwQFEBESExQVFhcYGRobHB0eHwECAwQF\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.thread.topic.as_deref(), Some("Budget"));
+    assert_eq!(
+        email.thread.thread_index.as_deref(),
+        Some("AdECAwQFEBESExQVFhcYGRobHB0eHwECAwQF")
+    );
+    assert_eq!(
+        email.thread.conversation_id.as_deref(),
+        Some("13121110-1514-1716-1819-1a1b1c1d1e1f")
+    );
+}
+
+#[test]
+fn test_invalid_thread_index_has_no_conversation_id() {
+    let raw = b"From: sender@example.com\r\n\
+                Thread-Index: not-base64!\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.thread.thread_index.is_some());
+    assert!(email.thread.conversation_id.is_none());
+}
+
+#[test]
+fn test_recipient_role() {
+    let raw = b"From: sender@example.com\r\n\
+                To: Me <me@example.com>, other@example.com\r\n\
+                Cc: boss@example.com\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.recipient_count(), 3);
+    assert_eq!(
+        email.recipient_role("ME@example.com"),
+        RecipientRole::DirectTo
+    );
+    assert_eq!(email.recipient_role("boss@example.com"), RecipientRole::Cc);
+    assert_eq!(
+        email.recipient_role("stranger@example.com"),
+        RecipientRole::NotAddressed
+    );
+}
+
+#[test]
+fn test_recipient_role_bulk() {
+    let to: Vec<String> = (0..12).map(|i| format!("user{i}@example.com")).collect();
+    let raw = format!(
+        "From: sender@example.com\r\nTo: {}\r\n\r\nBody",
+        to.join(", ")
+    );
+
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    assert_eq!(email.recipient_count(), 12);
+    assert_eq!(
+        email.recipient_role("user3@example.com"),
+        RecipientRole::BulkTo
+    );
+}
+
+#[test]
+fn test_attachments_with_rfc2231_filenames() {
+    let raw = b"From: sender@example.com\r\n\
+                Content-Type: multipart/mixed; boundary=\"mix\"\r\n\
+                \r\n\
+                --mix\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                See attached.\r\n\
+                --mix\r\n\
+                Content-Type: application/pdf\r\n\
+                Content-Disposition: attachment;\r\n \
+                filename*0*=utf-8''%E2%82%AC%20rates;\r\n \
+                filename*1=\".pdf\"\r\n\
+                \r\n\
+                PDFDATA\r\n\
+                --mix\r\n\
+                Content-Type: application/pdf; name*=iso-8859-1''r%E9sum%E9.pdf\r\n\
+                \r\n\
+                PDFDATA\r\n\
+                --mix--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+    let attachments = &email.body.attachments;
+
+    assert_eq!(email.body.text, "See attached.");
+    assert!(email.body.has_attachments);
+    assert_eq!(attachments.len(), 2);
+    assert_eq!(
+        attachments[0].filename.as_deref(),
+        Some("\u{20ac} rates.pdf")
+    );
+    assert_eq!(attachments[0].content_type, "application/pdf");
+    assert_eq!(attachments[0].size, 7);
+    assert_eq!(
+        attachments[1].filename.as_deref(),
+        Some("r\u{e9}sum\u{e9}.pdf")
+    );
+}
+
+#[test]
+fn test_multiple_from_mailboxes() {
+    let raw = b"From: \"Doe, Jane\" <jane@example.com>, mallory@evil.com\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.from.address, "jane@example.com");
+    assert_eq!(email.from.name.as_ref().unwrap().full, "Doe, Jane");
+    assert_eq!(email.from_addresses.len(), 2);
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "multiple_from")
+    );
+}
+
+#[test]
+fn test_repeated_from_headers() {
+    let raw = b"From: alice@example.com\r\n\
+                From: bob@attacker.net\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.from.address, "alice@example.com");
+    assert_eq!(email.from_addresses[1].address, "bob@attacker.net");
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "multiple_from")
+    );
+}
+
+#[test]
+fn test_forwarded_message_attachment() {
+    let raw = b"From: agent@support.com\r\n\
+                Subject: Fwd: Complaint\r\n\
+                Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+                \r\n\
+                --outer\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Forwarding this complaint.\r\n\
+                --outer\r\n\
+                Content-Type: message/rfc822\r\n\
+                \r\n\
+                From: Angry Customer <customer@example.com>\r\n\
+                Subject: Broken product\r\n\
+                \r\n\
+                It arrived broken.\r\n\
+                --outer--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.text, "Forwarding this complaint.");
+    assert!(email.body.attachments.is_empty());
+    assert_eq!(email.forwarded.len(), 1);
+    let inner = &email.forwarded[0];
+    assert_eq!(inner.from.address, "customer@example.com");
+    assert_eq!(inner.subject.original, "Broken product");
+    assert_eq!(inner.body.text, "It arrived broken.");
+}
+
+#[test]
+fn test_forwarded_message_dropped_with_warning() {
+    let raw = b"From: agent@support.com\r\n\
+                Subject: Fwd: Complaint\r\n\
+                Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+                \r\n\
+                --outer\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Forwarding this complaint.\r\n\
+                --outer\r\n\
+                Content-Type: message/rfc822\r\n\
+                \r\n\
+                Subject: No sender\r\n\
+                \r\n\
+                Who sent this?\r\n\
+                --outer--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.forwarded.is_empty());
+    assert!(
+        email
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("forwarded message 1 skipped"))
+    );
+}
+
+#[test]
+fn test_forwarded_messages_beyond_depth_warned() {
+    let mut raw = "From: level10@example.com\r\nSubject: Innermost\r\n\r\nDeepest.\r\n".to_string();
+    for level in (0..10).rev() {
+        raw = format!(
+            "From: level{level}@example.com\r\n\
+             Subject: Level {level}\r\n\
+             Content-Type: multipart/mixed; boundary=\"b{level}\"\r\n\
+             \r\n\
+             --b{level}\r\n\
+             Content-Type: message/rfc822\r\n\
+             \r\n\
+             {raw}\r\n\
+             --b{level}--\r\n"
+        );
+    }
+
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    let mut depth = 0;
+    let mut innermost = &email;
+    while let Some(inner) = innermost.forwarded.first() {
+        innermost = inner;
+        depth += 1;
+    }
+    assert_eq!(depth, 8);
+    assert_eq!(innermost.from.address, "level8@example.com");
+    assert!(
+        innermost
+            .warnings
+            .iter()
+            .any(|w| w.contains("nested deeper than 8 levels"))
+    );
+}
+
+#[test]
+fn test_normalize_whitespace_option() {
+    let raw = "From: sender@example.com\r\n\
+               Content-Type: text/plain; charset=utf-8\r\n\
+               \r\n\
+               Hello   there,\u{a0}\u{a0}friend\t!\n\n\n\n  Second   paragraph  "
+        .as_bytes();
+    let options = ParseOptions {
+        normalize_whitespace: true,
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+
+    assert_eq!(email.body.text, "Hello there, friend !\n\nSecond paragraph");
+    assert_eq!(email.body.char_count, email.body.text.len());
+
+    let untouched = parse_email(1, raw).unwrap();
+    assert!(untouched.body.text.contains("Hello   there"));
+}
+
+#[test]
+fn test_preserve_formatting_option() {
+    let raw = b"From: sender@example.com\r\n\
+                \r\n\
+                \n  Hello   there,\n\n\n  Call +1 555-123-4567  \n";
+    let options = ParseOptions {
+        normalize_whitespace: true,
+        preserve_formatting: true,
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+
+    assert_eq!(
+        email.body.raw_text.as_deref(),
+        Some("\n  Hello   there,\n\n\n  Call +1 555-123-4567  \n")
+    );
+    assert_eq!(email.body.text, "Hello there,\n\nCall +1 555-123-4567");
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+
+    assert!(parse_email(1, raw).unwrap().body.raw_text.is_none());
+}
+
+#[test]
+fn test_is_transactional() {
+    let receipt = b"From: orders@shop.com\r\n\
+                    Subject: Your order has been received\r\n\
+                    \r\n\
+                    Thanks for your purchase.";
+    let automated = b"From: noreply@shop.com\r\n\
+                      Subject: An update\r\n\
+                      \r\n\
+                      Order number 12345, total $19.99";
+    let personal = b"From: alice@example.com\r\n\
+                     Subject: Lunch?\r\n\
+                     \r\n\
+                     Are you free at noon?";
+    let marketing = b"From: deals@shop.com\r\n\
+                      Subject: Your order of savings awaits\r\n\
+                      List-Unsubscribe: <https://shop.com/unsub>\r\n\
+                      \r\n\
+                      Save $10 today";
+
+    assert!(parse_email(1, receipt).unwrap().is_transactional());
+    assert!(parse_email(2, automated).unwrap().is_transactional());
+    assert!(!parse_email(3, personal).unwrap().is_transactional());
+    assert!(!parse_email(4, marketing).unwrap().is_transactional());
+}
+
+#[test]
+fn test_precedence_header() {
+    let parse = |value: &str| {
+        let raw = format!(
+            "From: news@example.com\r\n\
+             Subject: News\r\n\
+             Precedence: {value}\r\n\
+             \r\n\
+             Body"
+        );
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    let bulk = parse("bulk");
+    assert_eq!(bulk.headers.precedence, Some(Precedence::Bulk));
+    assert!(bulk.metadata.is_automated);
+    assert!(bulk.metadata.is_mailing_list);
+    assert!((bulk.metadata.spam_score - 0.1).abs() < f32::EPSILON);
+
+    let list = parse("List");
+    assert_eq!(list.headers.precedence, Some(Precedence::List));
+    assert!(!list.metadata.is_automated);
+    assert!(list.metadata.is_mailing_list);
+    assert!(list.metadata.spam_indicators.is_empty());
+
+    let junk = parse("junk");
+    assert_eq!(junk.headers.precedence, Some(Precedence::Junk));
+    assert!(junk.metadata.is_automated);
+    assert!(!junk.metadata.is_mailing_list);
+    assert!(
+        junk.metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "precedence_junk")
+    );
+
+    let other = parse("first-class");
+    assert_eq!(other.headers.precedence, None);
+    assert!(!other.metadata.is_automated);
+}
+
+#[test]
+fn test_inline_forwarded_headers() {
+    let raw = b"From: bob@example.com\r\n\
+                Subject: Fwd: Quote request\r\n\
+                \r\n\
+                FYI, see below.\r\n\
+                \r\n\
+                ---------- Forwarded message ---------\r\n\
+                From: Alice Smith <alice@customer.com>\r\n\
+                Date: Mon, Jan 6, 2025 at 10:00 AM\r\n\
+                Subject: Quote request\r\n\
+                To: Bob <bob@example.com>, sales@example.com\r\n\
+                \r\n\
+                Could you send me a quote?";
+
+    let email = parse_email(1, raw).unwrap();
+    let forwarded = email.forwarded_headers.unwrap();
+
+    assert_eq!(forwarded.from.unwrap().address, "alice@customer.com");
+    assert_eq!(forwarded.to.len(), 2);
+    assert_eq!(forwarded.to[1].address, "sales@example.com");
+    assert_eq!(forwarded.subject.as_deref(), Some("Quote request"));
+    assert_eq!(
+        forwarded.date.as_deref(),
+        Some("Mon, Jan 6, 2025 at 10:00 AM")
+    );
+}
+
+#[test]
+fn test_inline_forwarded_headers_outlook() {
+    let raw = b"From: bob@example.com\r\n\
+                \r\n\
+                > -----Original Message-----\r\n\
+                > From: Alice Smith [mailto:alice@customer.com]\r\n\
+                > Sent: Monday, January 6, 2025 10:00 AM\r\n\
+                > To: Bob; carol@example.com\r\n\
+                > Subject: Quote request";
+
+    let email = parse_email(1, raw).unwrap();
+    let forwarded = email.forwarded_headers.unwrap();
+
+    let from = forwarded.from.unwrap();
+    assert_eq!(from.address, "alice@customer.com");
+    assert_eq!(from.name.unwrap().full, "Alice Smith");
+    assert_eq!(forwarded.to.len(), 1);
+    assert_eq!(forwarded.to[0].address, "carol@example.com");
+}
+
+#[test]
+fn test_lone_from_line_is_not_forward() {
+    let raw = b"From: bob@example.com\r\n\
+                \r\n\
+                From: the team at example.com\r\n\
+                \r\n\
+                Thanks";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.forwarded_headers.is_none());
+}
+
+#[test]
+fn test_bimi_headers() {
+    let raw = b"From: news@brand.com\r\n\
+                BIMI-Selector: v=BIMI1; s=spring;\r\n\
+                BIMI-Location: v=BIMI1; l=https://brand.com/logo.svg; a=https://brand.com/vmc.pem\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+    let bimi = email.headers.bimi.unwrap();
+
+    assert_eq!(bimi.selector.as_deref(), Some("spring"));
+    assert_eq!(bimi.location.as_deref(), Some("https://brand.com/logo.svg"));
+    assert_eq!(bimi.authority.as_deref(), Some("https://brand.com/vmc.pem"));
+
+    let plain = parse_email(2, b"From: a@example.com\r\n\r\nBody").unwrap();
+    assert!(plain.headers.bimi.is_none());
+}
+
+#[test]
+fn test_mailto_links_promoted_to_emails() {
+    let raw = b"From: sender@example.com\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Questions? <a href=\"mailto:support%40example.com?subject=Help\">Contact us</a></p>";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.extracted.emails.len(), 1);
+    assert_eq!(email.extracted.emails[0].address, "support@example.com");
+}
+
+#[test]
+fn test_metadata_ordering_is_deterministic() {
+    let raw = b"From: noreply@shop.com\r\n\
+                Message-ID: <1@bulk-sender.net>\r\n\
+                Subject: Urgent: act now\r\n\
+                Precedence: junk\r\n\
+                List-Unsubscribe: <https://shop.com/unsub>\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    let indicators: Vec<&str> = email
+        .metadata
+        .spam_indicators
+        .iter()
+        .map(|i| i.indicator.as_str())
+        .collect();
+    assert_eq!(
+        indicators,
+        vec![
+            "precedence_junk",
+            "urgency_language",
+            "messageid_domain_mismatch",
+            "noreply_sender",
+        ]
+    );
+
+    let categories: Vec<&str> = email
+        .metadata
+        .category_hints
+        .iter()
+        .map(|h| h.category.as_str())
+        .collect();
+    assert_eq!(categories, vec!["newsletter", "automated"]);
+}
+
+#[test]
+fn test_repeated_headers_preserved_in_order() {
+    let raw = b"Received: from relay2.example.net by mx.example.com\r\n\
+                From: sender@example.com\r\n\
+                Received: from relay1.example.net by relay2.example.net\r\n\
+                DKIM-Signature: v=1; d=example.com; s=one\r\n\
+                dkim-signature: v=1; d=esp.net; s=two\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.headers.all.len(), 5);
+    assert_eq!(email.headers.all[0].0, "received");
+    assert_eq!(
+        email.get_header_all("Received"),
+        vec![
+            "from relay2.example.net by mx.example.com",
+            "from relay1.example.net by relay2.example.net",
+        ]
+    );
+    assert_eq!(
+        email.get_header_all("DKIM-Signature"),
+        vec!["v=1; d=example.com; s=one", "v=1; d=esp.net; s=two"]
+    );
+    assert!(email.get_header_all("X-Missing").is_empty());
+}
+
+fn calendar_email(method: &str, extra: &str) -> Vec<u8> {
+    format!(
+        "From: organizer@example.com\r\n\
+         Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+         \r\n\
+         --b\r\n\
+         Content-Type: text/plain\r\n\
+         \r\n\
+         Planning meeting\r\n\
+         --b\r\n\
+         Content-Type: text/calendar; method={method}; charset=utf-8\r\n\
+         \r\n\
+         BEGIN:VCALENDAR\r\n\
+         METHOD:{method}\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:abc-123@example.com\r\n\
+         SUMMARY:Planning\\, Q1\r\n\
+         DTSTART;TZID=Europe/Berlin:20250106T100000\r\n\
+         ORGANIZER;CN=Org:mailto:organizer@example.com\r\n\
+         ATTENDEE;CN=Bob:mailto:bob@example.com\r\n\
+         {extra}END:VEVENT\r\n\
+         END:VCALENDAR\r\n\
+         --b--\r\n"
+    )
+    .into_bytes()
+}
+
+#[test]
+fn test_calendar_request() {
+    let email = parse_email(1, &calendar_email("REQUEST", "")).unwrap();
+    let event = email.calendar.as_ref().unwrap();
+
+    assert_eq!(event.method, Some(CalendarMethod::Request));
+    assert!(event.is_request());
+    assert!(!event.is_cancellation());
+    assert!(!event.is_update());
+    assert_eq!(event.summary.as_deref(), Some("Planning, Q1"));
+    assert_eq!(event.start.as_deref(), Some("20250106T100000"));
+    assert_eq!(event.organizer.as_deref(), Some("organizer@example.com"));
+    assert_eq!(event.attendees, vec!["bob@example.com"]);
+    assert!(
+        email
+            .metadata
+            .category_hints
+            .iter()
+            .any(|h| h.category == "calendar")
+    );
+}
+
+#[test]
+fn test_calendar_update_and_cancellation() {
+    let update = parse_email(1, &calendar_email("REQUEST", "SEQUENCE:2\r\n")).unwrap();
+    assert!(update.calendar.unwrap().is_update());
+
+    let cancel = parse_email(2, &calendar_email("CANCEL", "STATUS:CANCELLED\r\n")).unwrap();
+    let event = cancel.calendar.unwrap();
+    assert_eq!(event.method, Some(CalendarMethod::Cancel));
+    assert!(event.is_cancellation());
+    assert!(!event.is_request());
+}
+
+#[test]
+fn test_vcard_attachments() {
+    let raw = b"From: alice@example.com\r\n\
+                Subject: Contacts\r\n\
+                Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+                \r\n\
+                --b\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Here are the contacts\r\n\
+                --b\r\n\
+                Content-Type: text/vcard; charset=utf-8\r\n\
+                \r\n\
+                BEGIN:VCARD\r\n\
+                VERSION:3.0\r\n\
+                FN:Bob Smith\r\n\
+                ORG:Acme\\, Inc.;Sales\r\n\
+                TITLE:Account\r\n  \
+                 Manager\r\n\
+                EMAIL;TYPE=work:bob@acme.example\r\n\
+                item1.EMAIL:bob.smith@example.com\r\n\
+                TEL;TYPE=cell:+1 555 0100\r\n\
+                END:VCARD\r\n\
+                --b\r\n\
+                Content-Type: application/octet-stream; name=\"carol.vcf\"\r\n\
+                Content-Disposition: attachment; filename=\"carol.vcf\"\r\n\
+                \r\n\
+                BEGIN:VCARD\r\n\
+                VERSION:4.0\r\n\
+                FN:Carol Jones\r\n\
+                TEL;VALUE=uri:tel:+44-20-7946-0000\r\n\
+                END:VCARD\r\n\
+                --b--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.vcards.len(), 2);
+    let bob = &email.vcards[0];
+    assert_eq!(bob.name.as_deref(), Some("Bob Smith"));
+    assert_eq!(bob.organization.as_deref(), Some("Acme, Inc., Sales"));
+    assert_eq!(bob.title.as_deref(), Some("Account Manager"));
+    assert_eq!(
+        bob.emails,
+        vec!["bob@acme.example", "bob.smith@example.com"]
+    );
+    assert_eq!(bob.phones, vec!["+1 555 0100"]);
+    let carol = &email.vcards[1];
+    assert_eq!(carol.name.as_deref(), Some("Carol Jones"));
+    assert_eq!(carol.phones, vec!["+44-20-7946-0000"]);
+    assert!(carol.emails.is_empty());
+}
+
+#[test]
+fn test_cjk_body_is_text() {
+    let paragraph = "我们的项目进展顺利，请查看附件中的报告并在周五之前回复。".repeat(50);
+    let raw = format!(
+        "From: a@example.com\r\nSubject: Update\r\n\r\n{paragraph}\r\n\
+         请联系 zhang@example.cn 或致电 +1 555 010 0100。\r\n"
+    );
+    assert!(raw.len() > 1600);
+
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    assert!(email.warnings.is_empty());
+    assert_eq!(email.extracted.emails.len(), 1);
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+}
+
+#[test]
+fn test_binary_body_skips_extraction() {
+    let line = "hQEMA1x2c3RyaW5nK0NvbnRhY3Q6KzEtNTU1LTAxMDAvZm9vQGV4YW1wbGUuY29tLw";
+    let blob = vec![line; 30].join("\r\n");
+    let raw = format!("From: a@example.com\r\nSubject: Data\r\n\r\n{blob}\r\n");
+
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    assert!(email.extracted.urls.is_empty());
+    assert!(email.extracted.phone_numbers.is_empty());
+    assert!(
+        email
+            .warnings
+            .iter()
+            .any(|w| w.contains("entity extraction skipped"))
+    );
+
+    let mut binary = b"From: a@example.com\r\nSubject: Data\r\n\r\n".to_vec();
+    binary.extend((0..200u8).map(|b| b % 32));
+    binary.extend(b" call +1 555 010 0100");
+    let email = parse_email(2, &binary).unwrap();
+    assert!(email.extracted.phone_numbers.is_empty());
+    assert!(!email.warnings.is_empty());
+
+    let text = parse_email(
+        3,
+        b"From: a@example.com\r\nSubject: Hi\r\n\r\nCall +1 555 010 0100",
+    )
+    .unwrap();
+    assert!(text.warnings.is_empty());
+    assert_eq!(text.extracted.phone_numbers.len(), 1);
+}
+
+#[test]
+fn test_inline_pgp_signed_message() {
+    let raw = b"From: alice@example.com\r\n\
+                Subject: Signed\r\n\
+                \r\n\
+                -----BEGIN PGP SIGNED MESSAGE-----\r\n\
+                Hash: SHA256\r\n\
+                \r\n\
+                Call me at +1 555 010 0100.\r\n\
+                - -- \r\n\
+                Alice\r\n\
+                -----BEGIN PGP SIGNATURE-----\r\n\
+                \r\n\
+                iQEzBAEBCAAdFiEEx+y/Zm9vQGJhci5jb20gaHR0cHM6Ly9leGFtcGxlLmNvbQ==\r\n\
+                =AbCd\r\n\
+                -----END PGP SIGNATURE-----\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.security.signed);
+    assert!(!email.security.encrypted);
+    assert!(email.security.inline_pgp);
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+    assert!(email.extracted.urls.is_empty());
+    assert!(email.body.text.contains("BEGIN PGP SIGNATURE"));
+}
+
+#[test]
+fn test_inline_pgp_encrypted_and_pgp_mime() {
+    let encrypted = parse_email(
+        1,
+        b"From: alice@example.com\r\n\
+          Subject: Secret\r\n\
+          \r\n\
+          -----BEGIN PGP MESSAGE-----\r\n\
+          \r\n\
+          hQEMA+555+0100+aHR0cHM6Ly9leGFtcGxlLmNvbS9ub3Q=\r\n\
+          -----END PGP MESSAGE-----\r\n",
+    )
+    .unwrap();
+    assert!(encrypted.security.encrypted);
+    assert!(encrypted.security.inline_pgp);
+    assert!(encrypted.extracted.phone_numbers.is_empty());
+
+    let signed = parse_email(
+        2,
+        b"From: alice@example.com\r\n\
+          Subject: Signed\r\n\
+          Content-Type: multipart/signed; protocol=\"application/pgp-signature\"; boundary=\"s\"\r\n\
+          \r\n\
+          --s\r\n\
+          Content-Type: text/plain\r\n\
+          \r\n\
+          Hello\r\n\
+          --s\r\n\
+          Content-Type: application/pgp-signature\r\n\
+          \r\n\
+          sig\r\n\
+          --s--\r\n",
+    )
+    .unwrap();
+    assert!(signed.security.signed);
+    assert!(!signed.security.inline_pgp);
+}
+
+#[test]
+fn test_html_size_limit() {
+    let alternative = b"From: news@shop.example\r\n\
+                        Subject: News\r\n\
+                        Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+                        \r\n\
+                        --b\r\n\
+                        Content-Type: text/plain\r\n\
+                        \r\n\
+                        Plain version\r\n\
+                        --b\r\n\
+                        Content-Type: text/html\r\n\
+                        \r\n\
+                        <p>HTML version with a lot of markup</p>\r\n\
+                        --b--\r\n";
+    let options = ParseOptions {
+        html: HtmlOptions {
+            max_size: Some(16),
+            ..HtmlOptions::default()
+        },
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, alternative, &options).unwrap();
+    assert!(email.body.text_from_html.is_none());
+    assert_eq!(email.body.best_text(), "Plain version");
+    assert!(email.body.html.is_some());
+    assert!(email.warnings.iter().any(|w| w.contains("size limit")));
+
+    let unlimited = parse_email(2, alternative).unwrap();
+    assert_eq!(
+        unlimited.body.best_text(),
+        "HTML version with a lot of markup"
+    );
+    assert!(unlimited.warnings.is_empty());
+}
+
+#[test]
+fn test_html_source_positions() {
+    let html = "<div style=\"display:none\">Preview text</div>\
+                <table><tr><td>Order #A12345</td><td>&amp; more</td></tr></table>\
+                <p>Questions? Write to <b>help@shop.example</b></p>";
+    let raw = format!(
+        "From: shop@shop.example\r\nSubject: Order\r\nContent-Type: text/html\r\n\r\n{html}"
+    );
+    let options = ParseOptions {
+        html: HtmlOptions {
+            source_positions: true,
+            ..HtmlOptions::default()
+        },
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw.as_bytes(), &options).unwrap();
+    let position = email.extracted.emails[0].position;
+    assert!(html[position..].starts_with("help@shop.example"));
+    let reference = &email.extracted.order_numbers[0];
+    assert!(html[reference.position..].starts_with("A12345"));
+
+    let default = parse_email(2, raw.as_bytes()).unwrap();
+    let text = default.body.best_text();
+    assert!(text[default.extracted.emails[0].position..].starts_with("help@shop.example"));
+    assert!(email.warnings.is_empty());
+
+    let normalized = ParseOptions {
+        normalize_whitespace: true,
+        ..options
+    };
+    let email = parse_email_with_options(3, raw.as_bytes(), &normalized).unwrap();
+    assert!(
+        email
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("source positions not applied"))
+    );
+}
+
+#[test]
+fn test_utf7_body() {
+    let raw = b"From: helpdesk@example.com\r\n\
+                Content-Type: text/plain; charset=UTF-7\r\n\
+                \r\n\
+                Gr+APwA3w-e, 1 +- 1 = 2";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.text, "Grüße, 1 + 1 = 2");
+    assert!(email.warnings.is_empty());
+}
+
+#[test]
+fn test_koi8_body() {
+    let mut raw = b"From: sender@example.ru\r\n\
+                    Content-Type: text/plain; charset=koi8-r\r\n\
+                    \r\n"
+        .to_vec();
+    raw.extend([0xF0, 0xD2, 0xC9, 0xD7, 0xC5, 0xD4]);
+
+    let email = parse_email(1, &raw).unwrap();
+
+    assert_eq!(email.body.text, "Привет");
+}
+
+#[test]
+fn test_unsupported_charset_warning() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: =?x-unknown?Q?Hello?=\r\n\
+                Content-Type: text/plain; charset=x-unknown\r\n\
+                \r\n\
+                Plain body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.text, "Plain body");
+    assert_eq!(email.subject.original, "=?x-unknown?Q?Hello?=");
+    assert_eq!(
+        email.warnings,
+        vec![
+            "unsupported charset in encoded word: x-unknown",
+            "unsupported charset x-unknown, decoded as UTF-8",
+        ]
+    );
+}
+
+#[test]
+fn test_decode_modified_utf7() {
+    assert_eq!(decode_modified_utf7("INBOX").as_deref(), Some("INBOX"));
+    assert_eq!(
+        decode_modified_utf7("Tom &- Jerry").as_deref(),
+        Some("Tom & Jerry")
+    );
+    assert_eq!(
+        decode_modified_utf7("&ZeVnLIqe-").as_deref(),
+        Some("\u{65e5}\u{672c}\u{8a9e}")
+    );
+}
+
+#[test]
+fn test_email_diff() {
+    let raw = b"From: sender@example.com\r\n\
+                To: a@example.com, b@example.com\r\n\
+                Date: Wed, 01 Jan 2025 10:00:00 +0000\r\n\
+                Subject: Hello\r\n\
+                \r\n\
+                Body text";
+
+    let original = parse_email(1, raw).unwrap();
+    assert_eq!(original, parse_email(1, raw).unwrap());
+    assert!(original.diff(&original.clone()).is_empty());
+
+    let mut changed = original.clone();
+    changed.uid = 2;
+    changed.to[1].address = "c@example.com".into();
+    changed.cc.push(changed.from.clone());
+
+    let diffs = original.diff(&changed);
+    let paths: Vec<&str> = diffs.iter().map(|d| d.path.as_str()).collect();
+
+    assert_ne!(original, changed);
+    assert_eq!(paths, vec!["cc[0]", "to[1].address", "uid"]);
+    assert_eq!(diffs[0].left, None);
+    assert_eq!(diffs[2].left, Some(serde_json::json!(1)));
+    assert_eq!(diffs[2].right, Some(serde_json::json!(2)));
+}
+
+#[test]
+fn test_html_preheader_separated() {
+    let raw = b"From: deals@shop.com\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <div style=\"display:none; max-height:0; overflow:hidden\">\
+                Up to 50% off <span>this weekend</span></div>\
+                <div style=\"font-size: 14px\">Hello Jane,</div><p>Our sale starts now.</p>";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(
+        email.body.preheader.as_deref(),
+        Some("Up to 50% off this weekend")
+    );
+    assert_eq!(
+        email.body.text_from_html.as_deref(),
+        Some("Hello Jane,\nOur sale starts now.")
+    );
+}
+
+#[test]
+fn test_html_zero_font_size_hidden() {
+    let raw = b"From: deals@shop.com\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <span style=\"font-size:0px;mso-hide:all\">Preview</span><p>Visible</p>";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.body.preheader.as_deref(), Some("Preview"));
+    assert_eq!(email.body.text_from_html.as_deref(), Some("Visible"));
+}
+
+#[test]
+fn test_single_part_attachment() {
+    let raw = b"From: scanner@example.com\r\n\
+                Content-Type: application/pdf\r\n\
+                Content-Disposition: attachment; filename=\"report.pdf\"\r\n\
+                Content-Transfer-Encoding: base64\r\n\
+                \r\n\
+                JVBERi0xLjQKJcfs\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.body.text.is_empty());
+    assert!(email.body.has_attachments);
+    assert_eq!(email.body.attachments.len(), 1);
+    assert_eq!(
+        email.body.attachments[0].filename.as_deref(),
+        Some("report.pdf")
+    );
+    assert_eq!(email.body.attachments[0].content_type, "application/pdf");
+}
+
+#[test]
+fn test_multilingual_urgency() {
+    let parse = |subject: &str, options: &ParseOptions| {
+        let raw = format!(
+            "From: chef@example.de\r\n\
+             Subject: {subject}\r\n\
+             \r\n\
+             Body"
+        );
+        parse_email_with_options(1, raw.as_bytes(), options).unwrap()
+    };
+    let defaults = ParseOptions::default();
+
+    assert_eq!(
+        parse("Dringend: Vertrag", &defaults).metadata.urgency,
+        Urgency::High
+    );
+    assert_eq!(
+        parse("Respuesta urgente", &defaults).metadata.urgency,
+        Urgency::High
+    );
+    assert_eq!(
+        parse("Weekly notes", &defaults).metadata.urgency,
+        Urgency::Normal
+    );
+    assert_eq!(
+        parse("Frohe Weihnachten und schönen Heiligabend", &defaults)
+            .metadata
+            .urgency,
+        Urgency::Normal
+    );
+    assert_eq!(
+        parse("Critical thinking webinar recap", &defaults)
+            .metadata
+            .urgency,
+        Urgency::Normal
+    );
+
+    let english_only = ParseOptions {
+        urgency: UrgencyConfig::with_locales(&["en"]),
+        ..ParseOptions::default()
+    };
+    assert_eq!(
+        parse("Dringend: Vertrag", &english_only).metadata.urgency,
+        Urgency::Normal
+    );
+}
+
+#[test]
+fn test_importance_header_sets_priority() {
+    let raw = b"From: boss@example.com\r\n\
+                Importance: High\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.headers.priority, Some(Priority::High));
+    assert_eq!(email.metadata.urgency, Urgency::High);
+}
+
+#[test]
+fn test_subject_urgency_and_sentiment() {
+    let clickbait = parse_email(
+        1,
+        b"From: deals@shop.example\r\n\
+          Subject: URGENT: problem with your account\r\n\
+          \r\n\
+          Thank you for being a customer.",
+    )
+    .unwrap();
+    assert_eq!(clickbait.metadata.subject_urgency, Urgency::High);
+    assert_eq!(clickbait.metadata.subject_sentiment, Sentiment::Negative);
+    assert_eq!(clickbait.metadata.sentiment, Sentiment::Positive);
+
+    let header_only = parse_email(
+        2,
+        b"From: boss@example.com\r\nImportance: High\r\nSubject: Report\r\n\r\nBody",
+    )
+    .unwrap();
+    assert_eq!(header_only.metadata.urgency, Urgency::High);
+    assert_eq!(header_only.metadata.subject_urgency, Urgency::Normal);
+    assert_eq!(header_only.metadata.subject_sentiment, Sentiment::Neutral);
+}
+
+#[test]
+fn test_parse_headers_only() {
+    let raw = b"From: Jane Doe <jane@example.com>\r\n\
+                To: bob@example.com\r\n\
+                Subject: Re: Contract\r\n\
+                Date: Wed, 01 Jan 2025 10:00:00 +0000\r\n\
+                In-Reply-To: <abc@example.com>\r\n\
+                Authentication-Results: mx.example.com; spf=pass\r\n\
+                \r\n";
+
+    let email = parse_headers_only(7, raw).unwrap();
+
+    assert_eq!(email.uid, 7);
+    assert_eq!(email.from.address, "jane@example.com");
+    assert_eq!(email.to[0].address, "bob@example.com");
+    assert_eq!(email.subject.normalized, "Contract");
+    assert!(email.original_date.is_some());
+    assert!(email.thread.is_reply);
+    assert!(email.body.is_empty());
+    assert!(email.extracted.is_empty());
+    assert!(email.warnings.is_empty());
+}
+
+#[test]
+fn test_parse_headers_only_without_blank_line() {
+    let email = parse_headers_only(1, b"From: a@example.com\r\nSubject: Hi\r\n").unwrap();
+
+    assert_eq!(email.subject.original, "Hi");
+}
+
+#[test]
+fn test_structure_health_ok() {
+    let raw = b"From: sender@example.com\r\n\
+                Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+                \r\n\
+                --b\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Hello\r\n\
+                --b--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.structure_health().is_healthy());
+}
+
+#[test]
+fn test_structure_health_boundary_not_found() {
+    let raw = b"From: sender@example.com\r\n\
+                Content-Type: multipart/mixed; boundary=\"expected\"\r\n\
+                \r\n\
+                --actual\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Hello\r\n\
+                --actual--\r\n";
+
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(
+        email.structure_health().issues,
+        vec![
+            StructureIssue::BoundaryNotFound("expected".into()),
+            StructureIssue::MultipartWithoutParts,
+        ]
+    );
+}
+
+#[test]
+fn test_structure_health_missing_boundary_and_empty_text() {
+    let missing = b"From: sender@example.com\r\n\
+                    Content-Type: multipart/mixed\r\n\
+                    \r\n\
+                    Hello";
+    let empty = b"From: sender@example.com\r\n\
+                  Content-Type: text/plain\r\n\
+                  \r\n\
+                  \r\n";
+
+    let missing = parse_email(1, missing).unwrap();
+    let empty = parse_email(2, empty).unwrap();
+
+    assert_eq!(
+        missing.structure_health().issues,
+        vec![
+            StructureIssue::MissingBoundary,
+            StructureIssue::MultipartWithoutParts,
+        ]
+    );
+    assert_eq!(
+        empty.structure_health().issues,
+        vec![StructureIssue::EmptyTextPart]
+    );
+}
+
+#[test]
+fn test_received_ips() {
+    let raw = b"Received: from relay.example.net (relay.example.net [198.51.100.2])\r\n\
+                \tby mx.example.com; Wed, 01 Jan 2025 10:00:05 +0000\r\n\
+                Received: from laptop ([10.0.0.5]) by relay.example.net\r\n\
+                From: sender@example.com\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+    let ips: Vec<String> = email
+        .received_ips()
+        .iter()
+        .map(|ip| ip.address.to_string())
+        .collect();
+
+    assert_eq!(ips, vec!["198.51.100.2", "10.0.0.5"]);
+    assert!(email.received_ips()[1].is_private);
+}
+
+#[test]
+fn test_missing_subject_distinct_from_placeholder() {
+    let missing = parse_email(1, b"From: a@example.com\r\n\r\nBody").unwrap();
+    assert!(!missing.subject.was_present);
+    assert_eq!(missing.subject.original, "");
+    assert!(
+        missing
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "missing_subject")
+    );
+
+    let literal = parse_email(
+        1,
+        b"From: a@example.com\r\nSubject: (no subject)\r\n\r\nBody",
+    )
+    .unwrap();
+    assert!(literal.subject.was_present);
+    assert_eq!(literal.subject.original, "(no subject)");
+
+    let blank = parse_email(1, b"From: a@example.com\r\nSubject: \r\n\r\nBody").unwrap();
+    assert!(blank.subject.was_present);
+    assert!(blank.metadata.spam_indicators.is_empty());
+}
+
+#[test]
+fn test_parse_email_lossy_recovers_broken_headers() {
+    let raw = b"From: Sender <sender@example.com>\r\n\
+                Subject: Archived\r\n\
+                \rthis line is not a header\r\n\
+                To: rcpt@example.com\r\n\
+                \r\n\
+                Call me at +1 (555) 123-4567.";
+
+    // The lone CR starting a line makes strict header parsing fail
+    assert!(parse_email(1, raw).is_err());
+
+    let email = parse_email_lossy(1, raw).unwrap();
+    assert_eq!(email.from.address, "sender@example.com");
+    assert_eq!(email.subject.original, "Archived");
+    assert_eq!(email.to[0].address, "rcpt@example.com");
+    assert_eq!(email.body.text, "Call me at +1 (555) 123-4567.");
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+    assert_eq!(email.warnings.len(), 1);
+}
+
+#[test]
+fn test_parse_email_lossy_skips_leading_garbage() {
+    let raw = b"ial body of the previous message, cut off\r\n\
+                Regards, Bob\r\n\
+                \r\n\
+                From: sender@example.com\r\n\
+                Subject: Recovered\r\n\
+                \r\n\
+                Hello";
+
+    let email = parse_email_lossy(1, raw).unwrap();
+    assert_eq!(email.from.address, "sender@example.com");
+    assert_eq!(email.subject.original, "Recovered");
+    assert_eq!(email.body.text, "Hello");
+    assert_eq!(
+        email.warnings,
+        vec!["59 bytes of leading garbage before the headers skipped"]
+    );
+
+    // A header block quoted in the body is not mistaken for the real one
+    let forward = b"Subject: Fwd\r\n\
+                    \r\n\
+                    From: other@example.com\r\n\
+                    To: me@example.com\r\n\
+                    \r\n\
+                    Original";
+    assert!(parse_email_lossy(2, forward).is_err());
+}
+
+#[test]
+fn test_parse_email_lossy_matches_strict_parse() {
+    let raw = b"From: sender@example.com\r\n\
+                Subject: Hi\r\n\
+                Date: Mon, 1 Jan 2024 10:00:00 +0000\r\n\
+                \r\n\
+                Body";
+
+    assert_eq!(
+        parse_email_lossy(1, raw).unwrap(),
+        parse_email(1, raw).unwrap()
+    );
+}
+
+#[test]
+fn test_delivery_recipient_headers() {
+    let raw = b"Delivered-To: me@mydomain.example\r\n\
+                X-Original-To: sales@mydomain.example\r\n\
+                Delivered-To: catchall@mydomain.example\r\n\
+                From: customer@example.com\r\n\
+                To: Undisclosed recipients:;\r\n\
+                Subject: Quote\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+
+    let delivered: Vec<&str> = email
+        .headers
+        .delivered_to
+        .iter()
+        .map(|a| a.address.as_str())
+        .collect();
+    assert_eq!(
+        delivered,
+        vec!["me@mydomain.example", "catchall@mydomain.example"]
+    );
+    assert_eq!(
+        email.headers.x_original_to.unwrap().address,
+        "sales@mydomain.example"
+    );
+}
+
+fn intent_of(headers: &str, body: &str) -> Intent {
+    let raw = format!("{headers}\r\n\r\n{body}");
+    parse_email(1, raw.as_bytes()).unwrap().intent()
+}
+
+#[test]
+fn test_intent_classification() {
+    assert_eq!(
+        intent_of(
+            "From: shop@store.example\r\nSubject: Your order confirmation",
+            "Thanks for shopping."
+        ),
+        Intent::Confirmation
+    );
+    assert_eq!(
+        intent_of(
+            "From: news@brand.example\r\nList-Unsubscribe: <https://brand.example/u>\r\nSubject: Spring sale",
+            "New arrivals are here."
+        ),
+        Intent::SalesOutreach
+    );
+    assert_eq!(
+        intent_of(
+            "From: sdr@vendor.example\r\nSubject: Intro",
+            "Would you be interested in a quick call about our platform?"
+        ),
+        Intent::SalesOutreach
+    );
+    assert_eq!(
+        intent_of(
+            "From: noreply@ci.example\r\nSubject: Build finished",
+            "Pipeline #42 finished."
+        ),
+        Intent::Notification
+    );
+    assert_eq!(
+        intent_of(
+            "From: customer@example.com\r\nSubject: Broken again",
+            "The device is not working and I am still waiting for a refund."
+        ),
+        Intent::Complaint
+    );
+    assert_eq!(
+        intent_of(
+            "From: colleague@example.com\r\nSubject: Report",
+            "Could you send me the Q3 numbers by Friday?"
+        ),
+        Intent::Request
+    );
+    assert_eq!(
+        intent_of(
+            "From: colleague@example.com\r\nSubject: Slides",
+            "The slides from today are on the shared drive."
+        ),
+        Intent::Fyi
+    );
+}
+
+#[test]
+fn test_unsubscribe_mailto() {
+    let raw = b"From: news@shop.example\r\n\
+                Subject: Deals\r\n\
+                List-Unsubscribe: <https://shop.example/u/1>,\r\n \
+                <mailto:unsub@shop.example?subject=unsubscribe%20me&body=List%3A%20deals>\r\n\
+                \r\n\
+                Body";
+
+    let email = parse_email(1, raw).unwrap();
+    let mailto = email.headers.unsubscribe_mailto().unwrap();
+
+    assert_eq!(mailto.to.address, "unsub@shop.example");
+    assert_eq!(mailto.subject.as_deref(), Some("unsubscribe me"));
+    assert_eq!(mailto.body.as_deref(), Some("List: deals"));
+
+    let https_only = parse_email(
+        2,
+        b"From: news@shop.example\r\nList-Unsubscribe: <https://shop.example/u>\r\n\r\nBody",
+    )
+    .unwrap();
+    assert!(https_only.headers.unsubscribe_mailto().is_none());
+}
+
+#[test]
+fn test_min_category_confidence() {
+    let raw = b"From: noreply@shop.example\r\n\
+                List-Unsubscribe: <https://shop.example/u>\r\n\
+                Subject: Deals\r\n\
+                \r\n\
+                Body";
+    let categories = |options: &ParseOptions| -> Vec<String> {
+        parse_email_with_options(1, raw, options)
+            .unwrap()
+            .metadata
+            .category_hints
+            .into_iter()
+            .map(|h| h.category)
+            .collect()
+    };
+
+    assert_eq!(
+        categories(&ParseOptions::default()),
+        vec!["newsletter", "automated"]
+    );
+    let options = ParseOptions {
+        min_category_confidence: 0.85,
+        ..ParseOptions::default()
+    };
+    assert_eq!(categories(&options), vec!["newsletter"]);
+}
+
+#[test]
+fn test_recipient_domain_profile() {
+    let raw = b"From: sales@vendor.example\r\n\
+                To: buyer@acme.example, jane.doe@gmail.com, colleague@vendor.example\r\n\
+                Cc: cfo@acme.example, Buyer <BUYER@acme.example>, bob@yahoo.com\r\n\
+                Subject: Proposal\r\n\
+                \r\n\
+                Body";
+
+    let profile = parse_email(1, raw).unwrap().recipient_domain_profile();
+
+    assert_eq!(
+        profile,
+        DomainProfile {
+            freemail: 2,
+            corporate: 2,
+            same_domain: 1,
+        }
+    );
+    assert_eq!(profile.total(), 5);
+    assert!(!profile.is_mostly_corporate());
+}
+
+fn reply_email(from: &str, extra_headers: &str, body: &str) -> Vec<u8> {
+    format!(
+        "From: {from}\r\n\
+         Subject: Re: [#123] Printer broken\r\n\
+         In-Reply-To: <ticket-123@support.example>\r\n\
+         {extra_headers}\r\n\
+         {body}"
+    )
+    .into_bytes()
+}
+
+#[test]
+fn test_is_human_reply() {
+    let quoted = "\r\nOn Mon, Jan 6, 2025 at 10:00 Support wrote:\r\n> How can we help?\r\n";
+
+    let customer = reply_email(
+        "jane@customer.example",
+        "User-Agent: Thunderbird\r\n",
+        &format!("It still does not print.{quoted}"),
+    );
+    assert!(parse_email(1, &customer).unwrap().is_human_reply());
+
+    let update = reply_email(
+        "helpdesk@support.example",
+        "Auto-Submitted: auto-generated\r\n",
+        "Ticket #123 was updated.",
+    );
+    assert!(!parse_email(2, &update).unwrap().is_human_reply());
+
+    let quote_only = reply_email("jane@customer.example", "", &format!("> earlier{quoted}"));
+    let email = parse_email(3, &quote_only).unwrap();
+    assert_eq!(email.body.reply_text(), "");
+    assert!(!email.is_human_reply());
+
+    let noreply = reply_email("noreply@support.example", "", "Status changed to open.");
+    assert!(!parse_email(4, &noreply).unwrap().is_human_reply());
+}
+
+#[test]
+fn test_read_receipt_request() {
+    let raw = b"From: Sender <sender@example.com>\r\n\
+                Subject: Contract\r\n\
+                Disposition-Notification-To: Receipts <receipts@example.com>\r\n\
+                \r\n\
+                Please confirm.";
+    let email = parse_email(1, raw).unwrap();
+    assert!(email.requests_read_receipt());
+    assert_eq!(
+        email.read_receipt_address().unwrap().address,
+        "receipts@example.com"
+    );
+
+    let legacy = parse_email(
+        1,
+        b"From: sender@example.com\r\nX-Confirm-Reading-To: <sender@example.com>\r\n\r\nHi",
+    )
+    .unwrap();
+    assert_eq!(
+        legacy.read_receipt_address().unwrap().address,
+        "sender@example.com"
+    );
+
+    let none = parse_email(1, b"From: sender@example.com\r\n\r\nHi").unwrap();
+    assert!(!none.requests_read_receipt());
+}
+
+#[test]
+fn test_sender_key() {
+    let email = parse_email(
+        1,
+        b"From: John <John.Doe+promo@gmail.com>\r\nReturn-Path: <bounce@esp.example>\r\n\r\nHi",
+    )
+    .unwrap();
+    assert_eq!(email.sender_key(), "johndoe@gmail.com");
+
+    let no_domain = parse_email(
+        1,
+        b"From: john@\r\nReturn-Path: <Bounce@ESP.example>\r\n\r\nHi",
+    )
+    .unwrap();
+    assert_eq!(no_domain.sender_key(), "bounce@esp.example");
+}
+
+#[test]
+fn test_effective_sender() {
+    let ticket = parse_email(
+        1,
+        b"From: Helpdesk <noreply@support.example>\r\n\
+          Reply-To: Jane Customer <jane@customer.example>\r\n\
+          Subject: [#123] Printer broken\r\n\
+          \r\n\
+          Body",
+    )
+    .unwrap();
+    assert_eq!(ticket.effective_sender().address, "jane@customer.example");
+
+    let personal = parse_email(
+        2,
+        b"From: bob@example.com\r\n\
+          Reply-To: list@example.com\r\n\
+          Subject: Hello\r\n\
+          \r\n\
+          Body",
+    )
+    .unwrap();
+    assert_eq!(personal.effective_sender().address, "bob@example.com");
+
+    let no_reply_to = parse_email(
+        3,
+        b"From: noreply@support.example\r\nSubject: Hi\r\n\r\nBody",
+    )
+    .unwrap();
+    assert_eq!(
+        no_reply_to.effective_sender().address,
+        "noreply@support.example"
+    );
+}
+
+#[test]
+fn test_detected_languages_bilingual() {
+    let raw = "From: support@example.ca\r\n\
+               Subject: Your ticket / Votre billet\r\n\
+               \r\n\
+               Thank you for your message. We will reply to your request within two days.\r\n\
+               \r\n\
+               Merci pour votre message. Nous allons répondre à votre demande dans les deux jours.";
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    let languages = email.body.detected_languages();
+    let codes: Vec<&str> = languages.iter().map(|(code, _)| code.as_str()).collect();
+    assert_eq!(codes.len(), 2);
+    assert!(codes.contains(&"en") && codes.contains(&"fr"));
+    assert!(languages[1].1 >= 0.3);
+    assert_eq!(email.body.language.as_deref(), Some(codes[0]));
+
+    let german = parse_email(
+        1,
+        "From: a@example.de\r\n\r\nWir danken Ihnen für die Anfrage und melden uns bald."
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(german.body.language.as_deref(), Some("de"));
+    assert_eq!(german.body.detected_languages().len(), 1);
+}
+
+#[test]
+fn test_campaign_headers() {
+    let raw = b"From: news@shop.example\r\n\
+                Subject: Spring sale\r\n\
+                X-Mailgun-Sid: WyI2NzQ1In0=\r\n\
+                X-Mailgun-Campaign-Id: spring-2025\r\n\
+                Feedback-ID: spring-2025:shop:mailgun\r\n\
+                \r\n\
+                Everything 20% off.";
+    let email = parse_email(1, raw).unwrap();
+
+    let campaign = email.headers.campaign.as_ref().unwrap();
+    assert_eq!(campaign.platform, Some(CampaignPlatform::Mailgun));
+    assert_eq!(campaign.campaign_id.as_deref(), Some("spring-2025"));
+    assert_eq!(campaign.message_id.as_deref(), Some("WyI2NzQ1In0="));
+    assert_eq!(
+        campaign.feedback_id.as_deref(),
+        Some("spring-2025:shop:mailgun")
+    );
+
+    let hint = &email.metadata.category_hints[0];
+    assert_eq!(hint.category, "marketing-automation");
+    assert_eq!(hint.reason, "Sent through Mailgun");
+
+    let generic = parse_email(1, b"From: a@example.com\r\nX-Campaign-ID: 42\r\n\r\nHi").unwrap();
+    let campaign = generic.headers.campaign.unwrap();
+    assert_eq!(campaign.platform, None);
+    assert_eq!(campaign.campaign_id.as_deref(), Some("42"));
+
+    let plain = parse_email(1, b"From: a@example.com\r\n\r\nHi").unwrap();
+    assert!(plain.headers.campaign.is_none());
+}
+
+#[test]
+fn test_message_ids_normalized_for_threading() {
+    let original = parse_email(
+        1,
+        b"From: a@example.com\r\nMessage-ID: <root@example.com>\r\n\r\nHi",
+    )
+    .unwrap();
+    let reply = parse_email(
+        2,
+        b"From: b@example.com\r\n\
+          In-Reply-To:  root@example.com \r\n\
+          References: <root@example.com>\r\n\
+          \r\n\
+          Re",
+    )
+    .unwrap();
+
+    assert_eq!(reply.thread.in_reply_to, Some(original.message_id.clone()));
+    assert_eq!(reply.thread.references, vec![original.message_id]);
+}
+
+#[test]
+fn test_insecure_sensitive_link_indicator() {
+    let has_indicator = |body: &str| {
+        let raw = format!("From: security@bank.example\r\nSubject: Verify\r\n\r\n{body}");
+        parse_email(1, raw.as_bytes())
+            .unwrap()
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "insecure_sensitive_link")
+    };
+
+    assert!(has_indicator(
+        "Verify your account: http://bank.example/login?id=1"
+    ));
+    assert!(!has_indicator(
+        "Verify your account: https://bank.example/login?id=1"
+    ));
+    assert!(!has_indicator("Read more at http://blog.example/news"));
+}
+
+#[test]
+fn test_short_legal_form_words_not_a_lead() {
+    let raw = b"From: jane@example.com\r\n\
+                Subject: Rehearsal\r\n\
+                \r\n\
+                The AB band and Team SA meet Friday. Call me at +1 (555) 123-4567.";
+    let email = parse_email(1, raw).unwrap();
+
+    assert!(email.extracted.companies.is_empty());
+    assert!(
+        email
+            .metadata
+            .category_hints
+            .iter()
+            .all(|hint| hint.category != "lead")
+    );
+}
+
+#[test]
+fn test_recompute_metadata_after_enrichment() {
+    let raw = b"From: jane@example.com\r\n\
+                Subject: Intro\r\n\
+                \r\n\
+                Call me at +1 (555) 123-4567.";
+    let mut email = parse_email(1, raw).unwrap();
+    assert!(email.metadata.category_hints.is_empty());
+
+    email
+        .extracted
+        .companies
+        .push("Example Holdings Inc".into());
+    email.recompute_metadata();
+
+    assert_eq!(email.metadata.category_hints[0].category, "lead");
+}
+
+#[test]
+fn test_new_domain_indicator() {
+    let raw = b"From: billing@secure.fresh-bank.example\r\n\
+                Subject: Invoice\r\n\
+                Date: Mon, 10 Mar 2025 10:00:00 +0000\r\n\
+                \r\n\
+                See attached.";
+    let flagged = |registered: chrono::DateTime<Utc>| {
+        let mut domain_info = DomainInfo::default();
+        domain_info
+            .registered
+            .insert("fresh-bank.example".into(), registered);
+        let options = ParseOptions {
+            domain_info,
+            ..ParseOptions::default()
+        };
+        parse_email_with_options(1, raw, &options)
+            .unwrap()
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "new_domain")
+    };
+
+    assert!(flagged(Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()));
+    assert!(!flagged(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()));
+    assert!(
+        !parse_email(1, raw)
+            .unwrap()
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "new_domain")
+    );
+}
+
+#[test]
+fn test_to_rfc5322_round_trip() {
+    let raw = "From: \"Doe, Jane\" <jane@example.com>\r\n\
+               To: Bob <bob@example.com>, carol@example.com\r\n\
+               Cc: =?UTF-8?B?SsO8cmdlbg==?= <juergen@example.de>\r\n\
+               Subject: =?UTF-8?Q?Caf=C3=A9_plans?=\r\n\
+               Date: Tue, 4 Mar 2025 09:30:00 +0100\r\n\
+               Message-ID: <plans@example.com>\r\n\
+               In-Reply-To: <root@example.com>\r\n\
+               References: <root@example.com> <mid@example.com>\r\n\
+               X-Ticket: 42\r\n\
+               Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+               \r\n\
+               --b\r\n\
+               Content-Type: text/plain; charset=utf-8\r\n\
+               \r\n\
+               See you at the caf\u{e9}.\r\n\
+               --b\r\n\
+               Content-Type: text/html; charset=utf-8\r\n\
+               \r\n\
+               <p>See you at the caf\u{e9}.</p>\r\n\
+               --b--\r\n";
+    let original = parse_email(1, raw.as_bytes()).unwrap();
+
+    let rendered = original.to_rfc5322();
+    let reparsed = parse_email(1, rendered.as_bytes()).unwrap();
+
+    assert_eq!(reparsed.from_addresses, original.from_addresses);
+    assert_eq!(reparsed.to, original.to);
+    assert_eq!(reparsed.cc, original.cc);
+    assert_eq!(reparsed.subject, original.subject);
+    assert_eq!(reparsed.original_date, original.original_date);
+    assert_eq!(reparsed.message_id, original.message_id);
+    assert_eq!(reparsed.thread, original.thread);
+    assert_eq!(reparsed.headers.custom, original.headers.custom);
+    assert_eq!(reparsed.body.text.trim_end(), original.body.text.trim_end());
+    assert_eq!(reparsed.body.html, original.body.html);
+}
+
+#[test]
+fn test_misleading_links() {
+    let raw = b"From: service@paypal.example\r\n\
+                Subject: Verify\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Log in at <a href=\"https://paypal.com@evil.example/login\">https://paypal.com</a>.</p>\
+                <p><a href=\"https://www.example.com/news\">example.com</a>\
+                <a href=\"https://links.example.com/c/1\">www.example.com/news</a>\
+                <a href=\"https://evil.example/\">Click here</a></p>";
+    let email = parse_email(1, raw).unwrap();
+
+    let links = &email.extracted.misleading_links;
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].text, "https://paypal.com");
+    assert_eq!(links[0].text_domain, "paypal.com");
+    assert_eq!(links[0].href_domain, "evil.example");
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "misleading_link")
+    );
+}
+
+#[test]
+fn test_internal_date_received_date() {
+    let raw = b"From: a@example.com\r\n\
+                Date: Tue, 4 Mar 2025 09:30:00 +0100\r\n\
+                \r\n\
+                Hi";
+    let options = ParseOptions {
+        internal_date: Some(Utc.with_ymd_and_hms(2025, 3, 4, 8, 45, 0).unwrap()),
+        ..ParseOptions::default()
+    };
+
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+
+    assert_eq!(email.received_date, options.internal_date);
+    assert_eq!(
+        email.date,
+        Utc.with_ymd_and_hms(2025, 3, 4, 8, 30, 0).unwrap()
+    );
+    assert_eq!(email.delivery_delay(), Some(chrono::TimeDelta::minutes(15)));
+
+    let plain = parse_email(1, raw).unwrap();
+    assert_eq!(plain.received_date, None);
+    assert_eq!(plain.delivery_delay(), None);
+}
+
+#[test]
+fn test_add_attachment_entities() {
+    let raw = b"From: billing@vendor.example\r\n\
+                Subject: Invoice\r\n\
+                \r\n\
+                Questions? billing@vendor.example";
+    let mut email = parse_email(1, raw).unwrap();
+
+    let pdf_text = "Invoice INV-2025-001\nTotal: $120.00\nContact BILLING@vendor.example";
+    email.add_attachment_entities("invoice.pdf", ExtractedEntities::extract(pdf_text));
+
+    assert_eq!(email.attachment_entities.len(), 1);
+    assert_eq!(email.attachment_entities[0].0, "invoice.pdf");
+    assert_eq!(email.attachment_entities[0].1.amounts.len(), 1);
+    assert_eq!(email.extracted.emails.len(), 1);
+    assert_eq!(email.extracted.amounts[0].raw, "$120.00");
+    assert_eq!(email.extracted.order_numbers[0].value, "INV-2025-001");
+}
+
+#[test]
+fn test_possible_recipient_leak() {
+    let recipients: Vec<String> = (0..25).map(|i| format!("user{i}@example.com")).collect();
+    let parse = |extra: &str, body: &str| {
+        let raw = format!(
+            "From: jane@example.com\r\nSubject: Lab results\r\nTo: {}\r\n{extra}\r\n{body}",
+            recipients.join(", ")
+        );
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    let leak = parse("", "Hi John,\n\nYour results are attached.");
+    assert!(leak.possible_recipient_leak());
+    assert!(
+        leak.metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "possible_recipient_leak")
+    );
+
+    assert!(!parse("", "Hi all,\n\nMeeting moved.").possible_recipient_leak());
+    assert!(!parse("Precedence: bulk\r\n", "Hi John,\n\nNews.").possible_recipient_leak());
+}
+
+#[test]
+fn test_delivery_status_bounce_reasons() {
+    let raw = b"From: MAILER-DAEMON@mx.example.com\r\n\
+                Subject: Undelivered Mail Returned to Sender\r\n\
+                Content-Type: multipart/report; report-type=delivery-status; boundary=\"b\"\r\n\
+                \r\n\
+                --b\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Your message could not be delivered.\r\n\
+                --b\r\n\
+                Content-Type: message/delivery-status\r\n\
+                \r\n\
+                Reporting-MTA: dns; mx.example.com\r\n\
+                \r\n\
+                Final-Recipient: rfc822; gone@example.org\r\n\
+                Action: failed\r\n\
+                Status: 5.1.1\r\n\
+                Diagnostic-Code: smtp; 550 5.1.1 <gone@example.org>:\r\n \
+                 Recipient address rejected: User unknown\r\n\
+                \r\n\
+                Final-Recipient: rfc822; full@example.org\r\n\
+                Action: failed\r\n\
+                Status: 5.2.2\r\n\
+                \r\n\
+                Final-Recipient: rfc822; slow@example.net\r\n\
+                Action: delayed\r\n\
+                Diagnostic-Code: smtp; 451 4.4.7 Message delayed\r\n\
+                --b--\r\n";
+    let email = parse_email(1, raw).unwrap();
+    let reports = &email.delivery_status;
+    assert_eq!(reports.len(), 3);
+
+    assert_eq!(reports[0].recipient, "gone@example.org");
+    assert_eq!(reports[0].action.as_deref(), Some("failed"));
+    assert_eq!(reports[0].status.as_deref(), Some("5.1.1"));
+    assert_eq!(reports[0].reason, BounceReason::UnknownUser);
+    assert_eq!(reports[0].reason.to_string(), "unknown-user");
+    assert!(reports[0].is_hard_bounce());
+    assert!(
+        reports[0]
+            .diagnostic_code
+            .as_deref()
+            .unwrap()
+            .ends_with("User unknown")
+    );
+
+    assert_eq!(reports[1].reason, BounceReason::MailboxFull);
+    assert_eq!(reports[1].bounce, Some(BounceType::Soft));
+
+    // Status taken from the diagnostic text when the field is missing
+    assert_eq!(reports[2].status.as_deref(), Some("4.4.7"));
+    assert_eq!(reports[2].reason, BounceReason::Timeout);
+    assert_eq!(reports[2].bounce, Some(BounceType::Soft));
+
+    let plain = parse_email(2, b"From: a@example.com\r\n\r\nHello").unwrap();
+    assert!(plain.delivery_status.is_empty());
+}
+
+#[test]
+fn test_extract_all_parts() {
+    let raw = b"From: shop@example.com\r\n\
+                Subject: Your order\r\n\
+                Content-Type: multipart/alternative; boundary=\"b\"\r\n\
+                \r\n\
+                --b\r\n\
+                Content-Type: text/html\r\n\
+                \r\n\
+                <p>Questions? Write to help@example.com or\r\n\
+                <a href=\"https://example.com/track/42\">track your order</a>.</p>\r\n\
+                --b\r\n\
+                Content-Type: text/plain\r\n\
+                \r\n\
+                Questions? Call +1 (555) 123-4567.\r\n\
+                --b--\r\n";
+
+    let default = parse_email(1, raw).unwrap();
+    assert_eq!(default.extracted.phone_numbers.len(), 1);
+    assert!(default.extracted.urls.is_empty());
+
+    let options = ParseOptions {
+        extract_all_parts: true,
+        ..ParseOptions::default()
+    };
+    let email = parse_email_with_options(1, raw, &options).unwrap();
+    assert_eq!(email.body.primary_source(), BodySource::Text);
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+    assert_eq!(email.extracted.emails.len(), 1);
+    assert_eq!(email.extracted.emails[0].address, "help@example.com");
+    assert_eq!(email.extracted.urls.len(), 1);
+    assert_eq!(email.extracted.urls[0].url, "https://example.com/track/42");
+}
+
+#[test]
+fn test_broken_personalization() {
+    let parse = |subject: &str, body: &str| {
+        let raw = format!("From: news@shop.example\r\nSubject: {subject}\r\n\r\n{body}");
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    let email = parse(
+        "*|FNAME|*, your deals",
+        "Dear {{ first_name }},\n\nHi %RECIPIENT%, see *|FNAME|* below.",
+    );
+    assert_eq!(
+        email.unfilled_merge_tokens(),
+        vec!["*|FNAME|*", "{{ first_name }}", "%RECIPIENT%"]
+    );
+    assert!(email.metadata.is_automated);
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "broken_personalization")
+    );
+    assert!(
+        email
+            .metadata
+            .category_hints
+            .iter()
+            .any(|h| h.category == "bulk")
+    );
+
+    // Percent signs and percent-encoded URLs are not merge tokens
+    let plain = parse(
+        "Sale",
+        "Save 50% and 20% more at https://shop.example/caf%C3%A9%E2%80%99s",
+    );
+    assert!(plain.unfilled_merge_tokens().is_empty());
+    assert!(!plain.metadata.is_automated);
+}
+
+#[test]
+fn test_auto_submitted() {
+    let parse = |extra: &str| {
+        let raw =
+            format!("From: jane@example.com\r\nSubject: Re: Hi\r\n{extra}\r\nAway until Monday.");
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    let vacation = parse("Auto-Submitted: auto-replied; owner-email=\"jane@example.com\"\r\n");
+    assert_eq!(
+        vacation.headers.auto_submitted,
+        Some(AutoSubmitted::AutoReplied)
+    );
+    assert!(vacation.is_auto_reply());
+    assert!(vacation.metadata.is_automated);
+
+    let report = parse("Auto-Submitted: Auto-Notified\r\n");
+    assert_eq!(
+        report.headers.auto_submitted,
+        Some(AutoSubmitted::AutoNotified)
+    );
+    assert!(!report.is_auto_reply());
+    assert!(report.metadata.is_automated);
+
+    let person = parse("Auto-Submitted: no\r\n");
+    assert_eq!(person.headers.auto_submitted, Some(AutoSubmitted::No));
+    assert!(!person.metadata.is_automated);
+
+    assert!(parse("X-Autoreply: yes\r\n").is_auto_reply());
+    assert_eq!(parse("").headers.auto_submitted, None);
+}
+
+#[test]
+fn test_order_status() {
+    let parse = |subject: &str, body: &str| {
+        let raw = format!("From: orders@shop.example\r\nSubject: {subject}\r\n\r\n{body}");
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    assert_eq!(
+        parse("Order #1234 confirmed", "Thank you for your order!").order_status(),
+        Some(OrderStatus::Confirmed)
+    );
+    assert_eq!(
+        parse(
+            "Your package",
+            "Good news: your package is out for delivery."
+        )
+        .order_status(),
+        Some(OrderStatus::OutForDelivery)
+    );
+    assert_eq!(
+        parse(
+            "Delivered: Widget and 2 more items",
+            "Thank you for your order. It was handed to the carrier yesterday."
+        )
+        .order_status(),
+        Some(OrderStatus::Delivered)
+    );
+    assert_eq!(
+        parse("Update", "Your order has shipped and is on its way.").order_status(),
+        Some(OrderStatus::Shipped)
+    );
+    assert_eq!(parse("Hello", "Lunch tomorrow?").order_status(), None);
+}
+
+#[test]
+fn test_duplicate_headers() {
+    let raw = b"From: alice@example.com\r\n\
+                Message-ID: <one@example.com>\r\n\
+                Subject: Invoice\r\n\
+                Message-ID: <two@attacker.net>\r\n\
+                Subject: Urgent: wire transfer\r\n\
+                \r\n\
+                Body";
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(email.message_id.as_str(), "one@example.com");
+    assert_eq!(email.subject.original, "Invoice");
+    assert_eq!(email.duplicate_headers(), vec!["message-id", "subject"]);
+    assert!(
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "duplicate_header")
+    );
+
+    let clean = parse_email(2, b"From: a@example.com\r\nSubject: Hi\r\n\r\nBody").unwrap();
+    assert!(clean.duplicate_headers().is_empty());
+}
+
+#[test]
+fn test_survey_links() {
+    let raw = b"From: support@shop.example\r\n\
+                Subject: How did we do?\r\n\
+                \r\n\
+                Take our survey: https://www.surveymonkey.com/r/ABC123\r\n\
+                Visit https://shop.example for more.";
+    let email = parse_email(1, raw).unwrap();
+
+    let links = email.survey_links();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, "https://www.surveymonkey.com/r/ABC123");
+}
+
+#[test]
+fn test_mailing_list_recipient_threshold() {
+    let recipients: Vec<String> = (0..150).map(|i| format!("user{i}@example.com")).collect();
+    let raw = format!(
+        "From: events@example.com\r\nSubject: Town hall\r\nTo: {}\r\n\r\nSee you there.",
+        recipients.join(", ")
+    );
+
+    let default = parse_email(1, raw.as_bytes()).unwrap();
+    assert!(!default.metadata.is_mailing_list);
+
+    let options = ParseOptions {
+        mailing_list_recipients: Some(100),
+        ..ParseOptions::default()
+    };
+    let email = parse_email_with_options(1, raw.as_bytes(), &options).unwrap();
+    assert!(email.metadata.is_mailing_list);
+
+    let few = parse_email_with_options(
+        2,
+        b"From: a@example.com\r\nTo: b@example.com\r\n\r\nHi",
+        &options,
+    )
+    .unwrap();
+    assert!(!few.metadata.is_mailing_list);
+}
+
+#[test]
+fn test_from_domain_in_links() {
+    let parse = |from: &str, links: &str| {
+        let raw = format!("From: {from}\r\nSubject: Verify your account\r\n\r\n{links}");
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+    let indicator = |email: &email_extract::Email| {
+        email
+            .metadata
+            .spam_indicators
+            .iter()
+            .any(|i| i.indicator == "off_domain_links")
+    };
+
+    let legit = parse(
+        "security@mail.acme.com",
+        "Verify at https://www.acme.com/verify or see https://help.other.org",
+    );
+    assert!(legit.from_domain_in_links());
+    assert_eq!(legit.from_domain_link_ratio(), Some(0.5));
+    assert!(!indicator(&legit));
+
+    let phish = parse(
+        "security@acme.com",
+        "Verify at https://acme-login.example.net/verify",
+    );
+    assert!(!phish.from_domain_in_links());
+    assert_eq!(phish.from_domain_link_ratio(), Some(0.0));
+    assert!(indicator(&phish));
+
+    let uk = parse(
+        "orders@shop.co.uk",
+        "Track at https://track.shop.co.uk:8443/x",
+    );
+    assert_eq!(uk.from_domain_link_ratio(), None);
+    let uk = parse("orders@shop.co.uk", "See https://www.shop.co.uk:8443/x");
+    assert_eq!(uk.from_domain_link_ratio(), Some(1.0));
+
+    let de = parse("news@ibm.de", "Read more at https://www.ibm.de/news/2024");
+    assert_eq!(de.from_domain_link_ratio(), Some(1.0));
+    assert!(!indicator(&de));
+
+    assert_eq!(
+        parse("a@acme.com", "No links").from_domain_link_ratio(),
+        None
+    );
+}
+
+#[test]
+fn test_account_security() {
+    let parse = |from: &str, subject: &str, body: &str| {
+        let raw = format!("From: {from}\r\nSubject: {subject}\r\n\r\n{body}");
+        parse_email(1, raw.as_bytes()).unwrap()
+    };
+
+    let alert = parse(
+        "no-reply@accounts.example.com",
+        "Unusual sign-in to your account",
+        "We noticed a sign-in from a new device.",
+    );
+    assert!(alert.is_account_security());
+    assert_eq!(alert.metadata.urgency, Urgency::High);
+
+    let body_only = parse(
+        "noreply@service.example",
+        "Action needed",
+        "Use this verification code: 123456",
+    );
+    assert!(body_only.is_account_security());
+
+    let chat = parse(
+        "bob@example.com",
+        "Lunch",
+        "I had to reset your password on the shared laptop, sorry!",
+    );
+    assert!(!chat.is_account_security());
+    assert_eq!(chat.metadata.urgency, Urgency::Normal);
+}
+
+#[test]
+fn test_account_security_from_mailer_matches_recompute() {
+    let raw = b"From: alerts@shop.example\r\nX-Mailer: ShopNotify 2.1\r\nSubject: Hello\r\n\r\nWe saw a new sign-in. If it was not you, reset your password.";
+    let mut email = parse_email(1, raw).unwrap();
+    assert!(email.metadata.is_automated);
+    assert_eq!(email.metadata.urgency, Urgency::High);
+
+    let parsed = email.metadata.clone();
+    email.recompute_metadata();
+    assert_eq!(email.metadata.urgency, parsed.urgency);
+    assert_eq!(email.metadata.spam_indicators, parsed.spam_indicators);
+}
+
+#[test]
+fn test_max_entities_per_type() {
+    let addresses: Vec<String> = (0..50).map(|i| format!("user{i}@example.com")).collect();
+    let raw = format!(
+        "From: a@example.com\r\nSubject: List\r\n\r\n{}\nhttps://example.com",
+        addresses.join(" ")
+    );
+
+    let mut options = ParseOptions::default();
+    options.extraction.max_entities_per_type = Some(10);
+    let email = parse_email_with_options(1, raw.as_bytes(), &options).unwrap();
+    assert_eq!(email.extracted.emails.len(), 10);
+    assert_eq!(email.extracted.emails[0].address, "user0@example.com");
+    assert_eq!(email.extracted.urls.len(), 1);
+    assert!(email.extracted.truncated);
+
+    let unlimited = parse_email(1, raw.as_bytes()).unwrap();
+    assert_eq!(unlimited.extracted.emails.len(), 50);
+    assert!(!unlimited.extracted.truncated);
+}
+
+#[test]
+fn test_in_reply_to_multiple_ids() {
+    let raw = b"From: a@example.com\r\n\
+                In-Reply-To: <first@x.example> (Bob's message)\r\n <second@x.example>\r\n\
+                Subject: Re: Plans\r\n\
+                \r\n\
+                Sounds good";
+    let email = parse_email(1, raw).unwrap();
+
+    assert_eq!(
+        email.thread.in_reply_to_all,
+        vec![
+            MessageId::new("first@x.example"),
+            MessageId::new("second@x.example")
+        ]
+    );
+    assert_eq!(
+        email.thread.in_reply_to,
+        Some(MessageId::new("second@x.example"))
+    );
+
+    let reparsed = parse_email(2, email.to_rfc5322().as_bytes()).unwrap();
+    assert_eq!(
+        reparsed.thread.in_reply_to_all,
+        email.thread.in_reply_to_all
+    );
+}
+
+#[test]
+fn test_disclaimer_separated_from_body() {
+    let raw = "From: anna@corp.example\r\nSubject: Contract\r\n\r\n\
+               Hi Tom,\n\nthe signed contract is attached.\n\n\
+               Kind regards,\nAnna\n\n\
+               CONFIDENTIALITY NOTICE: This email and any attachments are confidential. \
+               If you have received it in error, notify legal@corp.example and delete it.";
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    let disclaimer = email.body.disclaimer.as_deref().unwrap();
+    assert!(disclaimer.starts_with("CONFIDENTIALITY NOTICE:"));
+    assert!(disclaimer.ends_with("delete it."));
+    assert_eq!(email.body.signature.as_deref(), Some("Kind regards,\nAnna"));
+    assert_eq!(
+        email.body.content_without_signature,
+        "Hi Tom,\n\nthe signed contract is attached."
+    );
+    assert_eq!(email.body.word_count, 10);
+    assert!(email.body.best_text().contains("CONFIDENTIALITY NOTICE"));
+    assert!(email.extracted.emails.is_empty());
+
+    // A message that opens with the phrase is not a disclaimer
+    let raw = "From: a@example.com\r\n\r\nThe information contained in this report is final.";
+    let email = parse_email(2, raw.as_bytes()).unwrap();
+    assert_eq!(email.body.disclaimer, None);
+}
+
+#[test]
+fn test_disclaimer_phrase_in_message_kept() {
+    let raw = "From: a@example.com\r\nSubject: Report\r\n\r\n\
+               Hi,\n\n\
+               The information contained in this report is summarized below.\n\
+               Call me at (555) 123-4567 or write to analyst@example.com.\n\n\
+               Revenue grew in all regions.\n\n\
+               Thanks,\nSam";
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    assert_eq!(email.body.disclaimer, None);
+    assert_eq!(email.extracted.phone_numbers.len(), 1);
+    assert_eq!(email.extracted.emails.len(), 1);
+    assert_eq!(
+        email.body.word_count,
+        email.body.best_text().split_whitespace().count()
+    );
+
+    // Legal wording alone does not make an opening line a disclaimer
+    let raw = "From: a@example.com\r\n\r\nHi,\n\nsee the notes.\n\n\
+               If you are not the intended recipient of the invite, just forward it to Kim.";
+    let email = parse_email(2, raw.as_bytes()).unwrap();
+    assert_eq!(email.body.disclaimer, None);
+}
+
+#[test]
+fn test_language_of_signature() {
+    let raw = "From: jan@firma.example\r\nSubject: Project update\r\n\r\n\
+               Hi team, the release is ready and we will ship it this week. \
+               Please review the notes and let me know if you have any questions.\n\
+               -- \n\
+               Jan Schmidt\n\
+               Diese E-Mail ist vertraulich und nur für den Empfänger bestimmt. \
+               Wenn Sie nicht der richtige Empfänger sind, löschen Sie diese Nachricht \
+               bitte und informieren Sie uns.";
+    let email = parse_email(1, raw.as_bytes()).unwrap();
+
+    assert_eq!(email.body.language.as_deref(), Some("en"));
+    assert_eq!(email.language_of_signature().as_deref(), Some("de"));
+    assert!(
+        email
+            .body
+            .detected_languages()
+            .iter()
+            .all(|(code, _)| code != "de")
+    );
+
+    let unsigned = parse_email(2, b"From: a@example.com\r\n\r\nThank you for the update").unwrap();
+    assert_eq!(unsigned.language_of_signature(), None);
+}
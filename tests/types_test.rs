@@ -11,7 +11,7 @@ fn test_message_id_new() {
 #[test]
 fn test_message_id_synthetic() {
     let id = MessageId::synthetic(42);
-    assert_eq!(id.as_str(), "<synthetic-42@local>");
+    assert_eq!(id.as_str(), "synthetic-42@local");
 }
 
 #[test]
@@ -27,6 +27,49 @@ fn test_message_id_equality() {
     assert_eq!(a, b);
 }
 
+#[test]
+fn test_message_id_normalized() {
+    let bracketed = MessageId::new(" <a@b> ");
+    let bare = MessageId::new("a@b");
+    assert_eq!(bracketed, bare);
+    assert_eq!(bracketed.as_str(), "a@b");
+    assert_eq!(bracketed.to_string(), "<a@b>");
+}
+
+#[test]
+fn test_message_id_is_valid() {
+    assert!(MessageId::new("<abc.123@mail.example.com>").is_valid());
+    assert!(MessageId::synthetic(1).is_valid());
+    assert!(!MessageId::new("no-domain").is_valid());
+    assert!(!MessageId::new("<@example.com>").is_valid());
+    assert!(!MessageId::new("a@b@c").is_valid());
+    assert!(!MessageId::new("a b@example.com").is_valid());
+}
+
+#[test]
+fn test_message_id_domain() {
+    assert_eq!(
+        MessageId::new("<abc@mail.example.com>").domain(),
+        Some("mail.example.com")
+    );
+    assert_eq!(MessageId::new("no-domain").domain(), None);
+}
+
+#[test]
+fn test_message_id_matches_domain() {
+    let id = MessageId::new("<abc@mail.example.com>");
+    assert_eq!(id.matches_domain("example.com"), Some(true));
+    assert_eq!(id.matches_domain("EXAMPLE.com"), Some(true));
+    assert_eq!(id.matches_domain("other.org"), Some(false));
+}
+
+#[test]
+fn test_message_id_synthetic_skips_domain_check() {
+    let id = MessageId::synthetic(7);
+    assert!(id.is_synthetic());
+    assert_eq!(id.matches_domain("example.com"), None);
+}
+
 // --- PersonName ---
 
 #[test]
@@ -117,6 +160,21 @@ fn test_email_address_parse_quoted_name() {
     assert_eq!(addr.address, "jane@mail.com");
 }
 
+#[test]
+fn test_email_address_parse_brackets_in_name() {
+    let addr = EmailAddress::parse("\"<Team> Support\" <team@x.com>").unwrap();
+    assert_eq!(addr.name.as_ref().unwrap().full, "<Team> Support");
+    assert_eq!(addr.address, "team@x.com");
+
+    let addr = EmailAddress::parse("<Team> Support <team@x.com>").unwrap();
+    assert_eq!(addr.name.as_ref().unwrap().full, "<Team> Support");
+    assert_eq!(addr.address, "team@x.com");
+
+    let addr = EmailAddress::parse(r#""Ops \"a<b>\" Desk" <ops@x.com>"#).unwrap();
+    assert_eq!(addr.name.as_ref().unwrap().full, "Ops \"a<b>\" Desk");
+    assert_eq!(addr.address, "ops@x.com");
+}
+
 #[test]
 fn test_email_address_parse_invalid() {
     assert!(EmailAddress::parse("not-an-email").is_none());
@@ -169,6 +227,18 @@ fn test_email_address_is_freemail() {
     assert!(!not_freemail.is_freemail());
 }
 
+#[test]
+fn test_email_address_canonical() {
+    let canonical = |addr: &str| EmailAddress::parse(addr).unwrap().canonical();
+
+    assert_eq!(canonical("John.Doe+news@GMail.com"), "johndoe@gmail.com");
+    assert_eq!(canonical("j.doe@googlemail.com"), "jdoe@gmail.com");
+    assert_eq!(
+        canonical("First.Last+tag@Company.io"),
+        "first.last+tag@company.io"
+    );
+}
+
 #[test]
 fn test_email_address_display_with_name() {
     let addr = EmailAddress::parse("Alice <alice@test.com>").unwrap();
@@ -266,14 +336,19 @@ fn test_priority_from_header() {
 fn test_body_is_empty() {
     let body = Body {
         text: String::new(),
+        raw_text: None,
         html: None,
         text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 0,
         char_count: 0,
         line_count: 0,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: String::new(),
     };
     assert!(body.is_empty());
@@ -283,14 +358,19 @@ fn test_body_is_empty() {
 fn test_body_not_empty_with_text() {
     let body = Body {
         text: "Hello".to_string(),
+        raw_text: None,
         html: None,
         text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 1,
         char_count: 5,
         line_count: 1,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: "Hello".to_string(),
     };
     assert!(!body.is_empty());
@@ -300,14 +380,19 @@ fn test_body_not_empty_with_text() {
 fn test_body_not_empty_with_html() {
     let body = Body {
         text: String::new(),
+        raw_text: None,
         html: Some("<p>Hi</p>".to_string()),
         text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 0,
         char_count: 0,
         line_count: 0,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: String::new(),
     };
     assert!(!body.is_empty());
@@ -317,14 +402,19 @@ fn test_body_not_empty_with_html() {
 fn test_body_best_text_prefers_text() {
     let body = Body {
         text: "Plain text".to_string(),
+        raw_text: None,
         html: Some("<p>HTML</p>".to_string()),
         text_from_html: Some("From HTML".to_string()),
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 2,
         char_count: 10,
         line_count: 1,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: "Plain text".to_string(),
     };
     assert_eq!(body.best_text(), "Plain text");
@@ -334,14 +424,19 @@ fn test_body_best_text_prefers_text() {
 fn test_body_best_text_falls_back_to_html_text() {
     let body = Body {
         text: String::new(),
+        raw_text: None,
         html: Some("<p>HTML</p>".to_string()),
         text_from_html: Some("From HTML".to_string()),
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 0,
         char_count: 0,
         line_count: 0,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: String::new(),
     };
     assert_eq!(body.best_text(), "From HTML");
@@ -351,22 +446,286 @@ fn test_body_best_text_falls_back_to_html_text() {
 fn test_body_best_text_empty() {
     let body = Body {
         text: String::new(),
+        raw_text: None,
         html: None,
         text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::Text,
         word_count: 0,
         char_count: 0,
         line_count: 0,
         language: None,
         has_attachments: false,
+        attachments: Vec::new(),
         signature: None,
+        disclaimer: None,
         content_without_signature: String::new(),
     };
     assert_eq!(body.best_text(), "");
 }
 
+#[test]
+fn test_body_primary_source_prefers_html_alternative() {
+    let body = Body {
+        text: "Plain text".to_string(),
+        raw_text: None,
+        html: Some("<p>HTML</p>".to_string()),
+        text_from_html: Some("From HTML".to_string()),
+        preheader: None,
+        preferred_source: BodySource::HtmlStripped,
+        word_count: 2,
+        char_count: 9,
+        line_count: 1,
+        language: None,
+        has_attachments: false,
+        attachments: Vec::new(),
+        signature: None,
+        disclaimer: None,
+        content_without_signature: "From HTML".to_string(),
+    };
+    assert_eq!(body.primary_source(), BodySource::HtmlStripped);
+    assert_eq!(body.best_text(), "From HTML");
+}
+
+#[test]
+fn test_body_primary_source_falls_back_to_text() {
+    let body = Body {
+        text: "Plain text".to_string(),
+        raw_text: None,
+        html: None,
+        text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::HtmlStripped,
+        word_count: 2,
+        char_count: 10,
+        line_count: 1,
+        language: None,
+        has_attachments: false,
+        attachments: Vec::new(),
+        signature: None,
+        disclaimer: None,
+        content_without_signature: "Plain text".to_string(),
+    };
+    assert_eq!(body.primary_source(), BodySource::Text);
+    assert_eq!(body.best_text(), "Plain text");
+}
+
+fn text_body(text: &str) -> Body {
+    Body {
+        text: text.to_string(),
+        raw_text: None,
+        html: None,
+        text_from_html: None,
+        preheader: None,
+        preferred_source: BodySource::Text,
+        word_count: text.split_whitespace().count(),
+        char_count: text.len(),
+        line_count: text.lines().count(),
+        language: None,
+        has_attachments: false,
+        attachments: Vec::new(),
+        signature: None,
+        disclaimer: None,
+        content_without_signature: text.to_string(),
+    }
+}
+
+#[test]
+fn test_body_salutation_personal() {
+    let salutation = text_body("Hi John,\n\nAre we still on?")
+        .salutation()
+        .unwrap();
+    assert_eq!(salutation.greeting, "Hi");
+    assert_eq!(salutation.name.unwrap().full, "John");
+    assert!(salutation.is_personalized);
+}
+
+#[test]
+fn test_body_salutation_with_title() {
+    let salutation = text_body("Dear Ms. Smith,\nThank you.")
+        .salutation()
+        .unwrap();
+    assert_eq!(salutation.greeting, "Dear");
+    assert_eq!(salutation.title.as_deref(), Some("Ms."));
+    assert_eq!(salutation.name.unwrap().full, "Smith");
+}
+
+#[test]
+fn test_body_salutation_generic() {
+    for text in ["Dear Customer,\nOffer", "Hi there!\nNews", "Hello,\nUpdate"] {
+        let salutation = text_body(text).salutation().unwrap();
+        assert!(!salutation.is_personalized, "{text}");
+        assert!(salutation.name.is_none(), "{text}");
+    }
+}
+
+#[test]
+fn test_body_salutation_absent() {
+    assert!(
+        text_body("Highlights from this week")
+            .salutation()
+            .is_none()
+    );
+    assert!(text_body("").salutation().is_none());
+}
+
+#[test]
+fn test_body_reading_time() {
+    let reply = format!(
+        "{}\n\nOn Mon, Bob wrote:\n> {}",
+        "word ".repeat(300),
+        "quoted ".repeat(500)
+    );
+    let body = text_body(&reply);
+    assert_eq!(body.reading_time(), std::time::Duration::from_secs(90));
+    assert_eq!(
+        body.reading_time_at(100),
+        std::time::Duration::from_secs(180)
+    );
+    assert_eq!(text_body("").reading_time(), std::time::Duration::ZERO);
+}
+
+#[test]
+fn test_body_complexity() {
+    let complexity = text_body("Hi Bob. Are you free today? See you!").complexity();
+    assert_eq!(complexity.sentences, 3);
+    assert!((complexity.avg_sentence_words - 8.0 / 3.0).abs() < 1e-9);
+    assert!((complexity.avg_word_chars - 3.25).abs() < 1e-9);
+
+    assert_eq!(text_body("").complexity(), TextComplexity::default());
+}
+
+#[test]
+fn test_body_text_direction() {
+    assert_eq!(
+        text_body("Hello, see you soon").text_direction(),
+        TextDirection::Ltr
+    );
+    assert_eq!(
+        text_body("שלום, נתראה בקרוב").text_direction(),
+        TextDirection::Rtl
+    );
+    assert_eq!(
+        text_body("مرحبا، رقم الطلب ABC").text_direction(),
+        TextDirection::Rtl
+    );
+    assert_eq!(
+        text_body("Meeting notes פגישה מחר בבוקר and more english words here").text_direction(),
+        TextDirection::Mixed
+    );
+    assert_eq!(
+        text_body("\u{200F}Order ABC confirmed").text_direction(),
+        TextDirection::Rtl
+    );
+    assert_eq!(text_body("12345").text_direction(), TextDirection::Ltr);
+}
+
+#[test]
+fn test_body_closing_same_line() {
+    let closing = text_body("Hi Bob,\n\nSee attached.\n\nThanks, Alice")
+        .closing()
+        .unwrap();
+    assert_eq!(closing.phrase, "Thanks");
+    assert_eq!(closing.name.unwrap().full, "Alice");
+}
+
+#[test]
+fn test_body_closing_next_line_multilingual() {
+    let closing = text_body("Bonjour,\nVoici le document.\n\nBien cordialement,\nMarie Dupont")
+        .closing()
+        .unwrap();
+    assert_eq!(closing.phrase, "Bien cordialement");
+    assert_eq!(closing.name.unwrap().full, "Marie Dupont");
+}
+
+#[test]
+fn test_body_closing_ignores_prose() {
+    let body = text_body("Thanks for the update, see you tomorrow.\n> Best,\n> Bob");
+    assert!(body.closing().is_none());
+
+    let closing = text_body("Groetjes,\nJan")
+        .closing_with_phrases(&["groetjes"])
+        .unwrap();
+    assert_eq!(closing.phrase, "Groetjes");
+    assert_eq!(closing.name.unwrap().full, "Jan");
+}
+
 // --- Sentiment ---
 
 #[test]
 fn test_sentiment_default() {
     assert_eq!(Sentiment::default(), Sentiment::Neutral);
 }
+
+#[test]
+fn test_body_parsed_signature() {
+    let body = Body {
+        signature: Some(
+            "Best regards,\nJane Smith\nHead of Sales\nAcme Widgets GmbH\n\
+             Tel: +1 (555) 123-4567\njane@acme.de | https://acme.de"
+                .into(),
+        ),
+        ..text_body("Please find the quote attached.")
+    };
+
+    let block = body
+        .parsed_signature(&ExtractionOptions::default())
+        .unwrap();
+
+    assert_eq!(block.name.unwrap().full, "Jane Smith");
+    assert_eq!(block.title.as_deref(), Some("Head of Sales"));
+    assert_eq!(block.company.as_deref(), Some("Acme Widgets GmbH"));
+    assert_eq!(block.phones.len(), 1);
+    assert_eq!(block.emails, vec!["jane@acme.de"]);
+    assert_eq!(block.urls[0].domain, "acme.de");
+}
+
+#[test]
+fn test_body_parsed_signature_title_at_company() {
+    let body = Body {
+        signature: Some("--\nBob Jones\nCTO at Initech".into()),
+        ..text_body("See you.")
+    };
+
+    let block = body
+        .parsed_signature(&ExtractionOptions::default())
+        .unwrap();
+
+    assert_eq!(block.name.unwrap().full, "Bob Jones");
+    assert_eq!(block.title.as_deref(), Some("CTO"));
+    assert_eq!(block.company.as_deref(), Some("Initech"));
+}
+
+#[test]
+fn test_body_parsed_signature_missing() {
+    assert!(
+        text_body("No signature here")
+            .parsed_signature(&ExtractionOptions::default())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_body_parsed_signature_uses_options() {
+    let body = Body {
+        signature: Some("--\nJane Smith\nTel: (555) 123-4567\nAcme Widgets Pty Ltd".into()),
+        ..text_body("See you.")
+    };
+    let options = ExtractionOptions {
+        min_phone_digits: 11,
+        company_suffixes: vec!["Pty Ltd".into()],
+        ..ExtractionOptions::default()
+    };
+
+    let block = body.parsed_signature(&options).unwrap();
+
+    assert!(block.phones.is_empty());
+    assert_eq!(block.company.as_deref(), Some("Acme Widgets Pty Ltd"));
+    assert_eq!(
+        body.parsed_signature(&ExtractionOptions::default())
+            .unwrap()
+            .phones
+            .len(),
+        1
+    );
+}